@@ -0,0 +1,105 @@
+use std::{fs, path::Path};
+
+use crate::solution::Part;
+
+/// Path to the manifest of known answers.
+pub const ANSWERS_PATH: &str = "resources/answers";
+
+/// The known answer of one part of a puzzle, for a given input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownAnswer {
+    pub year: u32,
+    pub day: u32,
+    pub part: Part,
+    /// Name of the input file in `resources/`.
+    pub input: String,
+    pub answer: String,
+}
+
+fn parse_line(line: &str) -> Option<KnownAnswer> {
+    let mut fields = line.split_whitespace();
+    let year = fields.next()?.parse::<u32>().ok()?;
+    let day = fields.next()?.parse::<u32>().ok()?;
+    let part = match fields.next()? {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => return None,
+    };
+    let input = fields.next()?.to_string();
+    let answer = fields.next()?.to_string();
+    if fields.next().is_some() {
+        return None;
+    }
+    Some(KnownAnswer {
+        year,
+        day,
+        part,
+        input,
+        answer,
+    })
+}
+
+/// Parse a manifest of known answers. Empty lines and lines starting with
+/// `#` are ignored.
+pub fn parse(contents: &str) -> Result<Vec<KnownAnswer>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(inx, line)| {
+            parse_line(line).ok_or_else(|| {
+                format!(
+                    "line {}: expected 'year day part input answer', found '{}'",
+                    inx + 1,
+                    line
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn load(path: &Path) -> Result<Vec<KnownAnswer>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+    parse(&contents).map_err(|err| format!("{}, {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest() {
+        let answers =
+            parse("# year day part input answer\n\n2023 6 2 day6_input 45128024\n").unwrap();
+        assert_eq!(
+            answers,
+            [KnownAnswer {
+                year: 2023,
+                day: 6,
+                part: Part::Two,
+                input: "day6_input".to_string(),
+                answer: "45128024".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn malformed_lines() {
+        for line in [
+            "2023 6 3 day6_input 1",
+            "2023 6 2 day6_input",
+            "2023 6 2 day6_input 1 2",
+            "year 6 2 day6_input 1",
+        ] {
+            assert!(parse_line(line).is_none(), "{}", line);
+        }
+        let err = parse("2023 1 1 day1_input 1\n2023 1 x day1_input 2").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{}", err);
+    }
+
+    #[test]
+    fn manifest_in_resources_is_valid() {
+        assert!(load(Path::new(ANSWERS_PATH)).is_ok());
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    hint::black_box,
+    ops::Add,
+    time::{Duration, Instant},
+};
+
+/// Summary of the wall time of repeated runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        if samples.is_empty() {
+            return Stats::default();
+        }
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+impl Add for Stats {
+    type Output = Stats;
+
+    fn add(self, other: Stats) -> Stats {
+        Stats {
+            min: self.min + other.min,
+            median: self.median + other.median,
+            max: self.max + other.max,
+        }
+    }
+}
+
+/// Timings of parsing and of each of the benchmarked parts of a solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Timings {
+    pub parse: Stats,
+    pub part1: Option<Stats>,
+    pub part2: Option<Stats>,
+}
+
+/// Run `f` the given number of times and collect its wall time.
+pub fn time<T>(iterations: usize, mut f: impl FnMut() -> T) -> Stats {
+    let mut samples = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect::<Vec<Duration>>();
+    Stats::from_samples(&mut samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_from_samples() {
+        let mut samples = [3, 1, 2, 5, 4].map(Duration::from_millis);
+        let stats = Stats::from_samples(&mut samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+}
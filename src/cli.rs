@@ -0,0 +1,711 @@
+use std::{
+    collections::HashMap,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::{self, KnownAnswer},
+    bench::Stats,
+    image::{Format, ImageOptions},
+    input::InputSource,
+    parallel::{self, Failure, Job, Report},
+    registry::Registry,
+    solution::Part,
+};
+
+pub const USAGE: &str = "\
+Usage: adevent_of_code <command> [options]
+
+Commands:
+  run       Run the selected solutions and print their answers
+  verify    Check the selected solutions (default: all) against the known answers
+  bench     Time parsing and each part of the selected solutions
+  help      Print this message
+
+Options:
+  --year <year>     Only select solutions from this year (default: latest year)
+  --day <days>      Select a single day (7) or an inclusive range of days (3..10)
+  --part <part>     Only run part 1 or part 2 (default: both)
+  --all             Select every registered solution that has an input file
+  --input <path>    Read the puzzle input from a file (single day only, not for verify)
+  --stdin           Read the puzzle input from standard input (single day only, not for verify)
+  --example [n]     Use the example input, e.g. resources/day10_example2 for --example 2
+                    (not for verify)
+  --iterations <n>  Number of times to run each step when benchmarking (default: 10)
+  --parallel        Run all selected parts concurrently and print a summary table
+  --jobs <n>        Number of threads for --parallel (default: number of CPUs)
+  --images <dir>    Save images of the puzzle grids to a directory, where supported
+                    (not while benchmarking)
+  --image-format <format>
+                    Format of the saved images, png or ppm (default: png)
+  --cell-size <n>   Size of each grid cell in the saved images in pixels (default: 4)
+  -v, --verbose     Print diagnostic output from the solutions, repeat for more";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub command: Command,
+    /// How much diagnostic output to print, increased by each -v.
+    pub verbosity: u8,
+    /// Where and how to save images of the puzzle grids.
+    pub images: Option<ImageOptions>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run(Selection),
+    /// Run the selection on the given number of threads.
+    RunParallel(Selection, usize),
+    Verify(Selection),
+    Bench(Selection, usize),
+    Help,
+}
+
+/// Which solutions and parts a command should run.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Selection {
+    year: Option<u32>,
+    days: Option<RangeInclusive<u32>>,
+    part: Option<Part>,
+    all: bool,
+    input: InputSource,
+}
+
+impl Selection {
+    /// All (year, day, part) combinations in the registry matching the selection.
+    pub fn resolve(&self, registry: &Registry) -> Result<Vec<(u32, u32, Part)>, String> {
+        let year = if self.all && self.year.is_none() {
+            None
+        } else {
+            Some(match self.year {
+                Some(year) => year,
+                None => registry.latest_year().ok_or("no solutions registered")?,
+            })
+        };
+
+        let parts = match self.part {
+            Some(part) => vec![part],
+            None => vec![Part::One, Part::Two],
+        };
+
+        let mut selected = Vec::new();
+        for (y, d) in registry.keys() {
+            if year.is_some_and(|year| year != y) {
+                continue;
+            }
+            if let Some(days) = &self.days {
+                if !days.contains(&d) {
+                    continue;
+                }
+            } else if self.all && self.input.path(y, d).is_some_and(|path| !path.exists()) {
+                // Days without an input yet are only run when selected explicitly
+                continue;
+            }
+            for part in &parts {
+                selected.push((y, d, *part));
+            }
+        }
+
+        if selected.is_empty() {
+            return Err(match (&self.days, year) {
+                (Some(days), Some(year)) if days.start() == days.end() => {
+                    format!("no solution for {} day {}", year, days.start())
+                }
+                _ => "no solutions match the selection".to_string(),
+            });
+        }
+
+        Ok(selected)
+    }
+}
+
+fn parse_number(option: &str, value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("invalid value for {}: '{}'", option, value))
+}
+
+fn parse_days(value: &str) -> Result<RangeInclusive<u32>, String> {
+    if let Some((first, last)) = value.split_once("..") {
+        let last = last.strip_prefix('=').unwrap_or(last);
+        let first = parse_number("--day", first)?;
+        let last = parse_number("--day", last)?;
+        if first > last {
+            return Err(format!("empty day range: '{}'", value));
+        }
+        Ok(first..=last)
+    } else {
+        let day = parse_number("--day", value)?;
+        Ok(day..=day)
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, String> {
+    match value.trim() {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("invalid value for --part: '{}'", value)),
+    }
+}
+
+fn parse_selection(args: &[String], default_all: bool) -> Result<Selection, String> {
+    let mut selection = Selection {
+        all: default_all,
+        ..Selection::default()
+    };
+
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--year" => selection.year = Some(parse_number(arg, value()?)?),
+            "--day" => selection.days = Some(parse_days(value()?)?),
+            "--part" => selection.part = Some(parse_part(value()?)?),
+            "--all" => selection.all = true,
+            "--input" => selection.input = InputSource::File(PathBuf::from(value()?)),
+            "--stdin" => selection.input = InputSource::Stdin,
+            "--example" => {
+                let suffix = args.next_if(|next| !next.starts_with("--")).cloned();
+                selection.input = InputSource::Example(suffix);
+            }
+            _ => return Err(format!("unknown option: '{}'", arg)),
+        }
+    }
+
+    if !selection.all && selection.days.is_none() {
+        return Err("select a day with --day, or use --all".to_string());
+    }
+
+    Ok(selection)
+}
+
+/// Remove an option and its value from the arguments, returning the value.
+fn take_option(args: &mut Vec<String>, option: &str) -> Result<Option<String>, String> {
+    match args.iter().position(|arg| arg == option) {
+        Some(inx) if inx + 1 < args.len() => {
+            let value = args.remove(inx + 1);
+            args.remove(inx);
+            Ok(Some(value))
+        }
+        Some(_) => Err(format!("missing value for {}", option)),
+        None => Ok(None),
+    }
+}
+
+/// Remove a flag from the arguments, returning whether it was given.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != len
+}
+
+/// Take the image options from `args`, which only apply with `--images`.
+fn parse_image_options(args: &mut Vec<String>) -> Result<Option<ImageOptions>, String> {
+    let dir = take_option(args, "--images")?;
+    let format = take_option(args, "--image-format")?;
+    let cell_size = take_option(args, "--cell-size")?;
+    let Some(dir) = dir else {
+        if format.is_some() || cell_size.is_some() {
+            return Err("--image-format and --cell-size need --images".to_string());
+        }
+        return Ok(None);
+    };
+
+    let mut options = ImageOptions::new(PathBuf::from(dir));
+    if let Some(format) = format {
+        options.format = match format.as_str() {
+            "png" => Format::Png,
+            "ppm" => Format::Ppm,
+            _ => return Err(format!("invalid image format: '{}'", format)),
+        };
+    }
+    if let Some(cell_size) = cell_size {
+        options.cell_size = parse_number("--cell-size", &cell_size)? as usize;
+        if options.cell_size == 0 {
+            return Err("--cell-size must be at least 1".to_string());
+        }
+    }
+    Ok(Some(options))
+}
+
+/// Parse the command line arguments (excluding the program name).
+pub fn parse_args(args: &[String]) -> Result<Args, String> {
+    // The verbosity flags can be given anywhere
+    let mut verbosity = 0;
+    let mut args = args
+        .iter()
+        .filter(|arg| match arg.as_str() {
+            "--verbose" => {
+                verbosity += 1;
+                false
+            }
+            _ if arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == 'v') => {
+                verbosity += arg.len() as u8 - 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect::<Vec<String>>();
+    let images = parse_image_options(&mut args)?;
+
+    let command = match args.split_first() {
+        None => Command::Help,
+        Some((command, rest)) => match command.as_str() {
+            "run" => {
+                let mut rest = rest.to_vec();
+                let jobs = match take_option(&mut rest, "--jobs")? {
+                    Some(value) => Some(parse_number("--jobs", &value)? as usize),
+                    None => None,
+                };
+                let parallel = take_flag(&mut rest, "--parallel");
+                if jobs == Some(0) {
+                    return Err("--jobs must be at least 1".to_string());
+                }
+                let selection = parse_selection(&rest, false)?;
+                if parallel || jobs.is_some() {
+                    Command::RunParallel(selection, jobs.unwrap_or_else(parallel::default_threads))
+                } else {
+                    Command::Run(selection)
+                }
+            }
+            "verify" => {
+                let selection = parse_selection(rest, true)?;
+                // The known answers belong to the inputs listed in the answers file
+                if selection.input != InputSource::Default {
+                    return Err(
+                        "--input, --stdin and --example cannot be used with verify".to_string()
+                    );
+                }
+                Command::Verify(selection)
+            }
+            "bench" => {
+                let mut rest = rest.to_vec();
+                let iterations = match take_option(&mut rest, "--iterations")? {
+                    Some(value) => parse_number("--iterations", &value)? as usize,
+                    None => 10,
+                };
+                if iterations == 0 {
+                    return Err("--iterations must be at least 1".to_string());
+                }
+                Command::Bench(parse_selection(&rest, false)?, iterations)
+            }
+            "help" | "--help" | "-h" => Command::Help,
+            _ => return Err(format!("unknown command: '{}'", command)),
+        },
+    };
+
+    Ok(Args {
+        command,
+        verbosity,
+        images,
+    })
+}
+
+fn run(registry: &Registry, selection: &Selection) -> Result<(), String> {
+    let selected = selection.resolve(registry)?;
+
+    if selection.input.is_fixed()
+        && selected
+            .iter()
+            .any(|s| (s.0, s.1) != (selected[0].0, selected[0].1))
+    {
+        return Err("--input and --stdin can only be used with a single day".to_string());
+    }
+
+    // Read the input once per day, since both parts use the same input. A
+    // missing input or a parse error only fails the parts of that day.
+    let mut input: Option<((u32, u32), Result<String, String>)> = None;
+    let mut failed = 0;
+    for (year, day, part) in selected {
+        let solution = registry.get(year, day).unwrap();
+
+        if input.as_ref().map(|(key, _)| *key) != Some((year, day)) {
+            input = Some(((year, day), selection.input.read(year, day)));
+        }
+        let (_, contents) = input.as_ref().unwrap();
+
+        let answer = match contents {
+            Ok(contents) => solution.run(contents, part).map_err(|err| err.to_string()),
+            Err(err) => Err(err.clone()),
+        };
+        match answer {
+            Ok(answer) => println!("{} day {:>2} part {}: {}", year, day, part, answer),
+            Err(err) => {
+                failed += 1;
+                eprintln!("{} day {:>2} part {}: {}", year, day, part, err);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} solutions failed", failed));
+    }
+    Ok(())
+}
+
+fn run_parallel(registry: &Registry, selection: &Selection, threads: usize) -> Result<(), String> {
+    let selected = selection.resolve(registry)?;
+
+    if selection.input.is_fixed()
+        && selected
+            .iter()
+            .any(|s| (s.0, s.1) != (selected[0].0, selected[0].1))
+    {
+        return Err("--input and --stdin can only be used with a single day".to_string());
+    }
+
+    // Read the inputs up front, a missing input only fails the parts of that day
+    let mut inputs: HashMap<(u32, u32), Result<Arc<str>, String>> = HashMap::new();
+    let mut jobs = Vec::new();
+    let mut reports = Vec::new();
+    for (year, day, part) in selected {
+        let input = inputs
+            .entry((year, day))
+            .or_insert_with(|| selection.input.read(year, day).map(Arc::from));
+        match input {
+            Ok(contents) => jobs.push(Job {
+                year,
+                day,
+                part,
+                solution: registry.get(year, day).unwrap(),
+                contents: contents.clone(),
+            }),
+            Err(err) => reports.push(Report {
+                year,
+                day,
+                part,
+                result: Err(Failure::Input(err.clone())),
+                time: Duration::ZERO,
+            }),
+        }
+    }
+
+    let start = Instant::now();
+    reports.extend(parallel::run(&jobs, threads));
+    let elapsed = start.elapsed();
+    reports.sort_by_key(|report| (report.year, report.day, report.part));
+
+    println!("{:<18} {:<6} {:>12}  answer", "", "status", "time");
+    let mut failed = 0;
+    for report in &reports {
+        let (status, details) = match &report.result {
+            Ok(answer) => ("ok", answer.to_string()),
+            Err(failure) => {
+                failed += 1;
+                ("FAIL", failure.to_string())
+            }
+        };
+        println!(
+            "{:<18} {:<6} {:>12}  {}",
+            format!("{} day {:>2} part {}", report.year, report.day, report.part),
+            status,
+            format!("{:.1?}", report.time),
+            details
+        );
+    }
+
+    let total: Duration = reports.iter().map(|report| report.time).sum();
+    println!(
+        "\n{} succeeded, {} failed, in {:.1?} on {} threads ({:.1?} of solution time)",
+        reports.len() - failed,
+        failed,
+        elapsed,
+        threads,
+        total
+    );
+
+    if failed > 0 {
+        return Err(format!("{} solutions failed", failed));
+    }
+    Ok(())
+}
+
+/// Outcome of checking a solution against a known answer.
+enum Verdict {
+    Pass,
+    Mismatch(String),
+    Fail(String),
+}
+
+fn check(registry: &Registry, known: &KnownAnswer) -> Verdict {
+    let Some(solution) = registry.get(known.year, known.day) else {
+        return Verdict::Fail("no solution registered".to_string());
+    };
+    let input = InputSource::File(PathBuf::from("resources").join(&known.input));
+    let contents = match input.read(known.year, known.day) {
+        Ok(contents) => contents,
+        Err(err) => return Verdict::Fail(err),
+    };
+    match solution.run(&contents, known.part) {
+        Ok(answer) if answer.to_string() == known.answer => Verdict::Pass,
+        Ok(answer) => Verdict::Mismatch(format!("expected {}, got {}", known.answer, answer)),
+        Err(err) => Verdict::Fail(err.to_string()),
+    }
+}
+
+fn verify(registry: &Registry, selection: &Selection) -> Result<(), String> {
+    let selected = selection.resolve(registry)?;
+    let known_answers = answers::load(Path::new(answers::ANSWERS_PATH))?;
+
+    let (mut passed, mut mismatched, mut failed) = (0, 0, 0);
+    for known in known_answers
+        .iter()
+        .filter(|k| selected.contains(&(k.year, k.day, k.part)))
+    {
+        let (status, details) = match check(registry, known) {
+            Verdict::Pass => {
+                passed += 1;
+                ("PASS", known.answer.clone())
+            }
+            Verdict::Mismatch(details) => {
+                mismatched += 1;
+                ("MISMATCH", details)
+            }
+            Verdict::Fail(details) => {
+                failed += 1;
+                ("FAIL", details)
+            }
+        };
+        println!(
+            "{:<8} {} day {:>2} part {} ({}): {}",
+            status, known.year, known.day, known.part, known.input, details
+        );
+    }
+
+    // Solutions that can not be checked
+    let mut unknown = 0;
+    for (year, day, part) in &selected {
+        if !known_answers
+            .iter()
+            .any(|k| (k.year, k.day, k.part) == (*year, *day, *part))
+        {
+            unknown += 1;
+            println!(
+                "{:<8} {} day {:>2} part {}: no known answer",
+                "UNKNOWN", year, day, part
+            );
+        }
+    }
+
+    println!(
+        "\n{} passed, {} mismatched, {} failed, {} without a known answer",
+        passed, mismatched, failed, unknown
+    );
+
+    if mismatched + failed > 0 {
+        return Err(format!("{} solutions did not pass", mismatched + failed));
+    }
+    Ok(())
+}
+
+fn print_stats(label: &str, stats: &Stats) {
+    println!(
+        "{:<20} {:>12} {:>12} {:>12}",
+        label,
+        format!("{:.1?}", stats.min),
+        format!("{:.1?}", stats.median),
+        format!("{:.1?}", stats.max)
+    );
+}
+
+fn bench(registry: &Registry, selection: &Selection, iterations: usize) -> Result<(), String> {
+    let selected = selection.resolve(registry)?;
+
+    // Group the selected parts by day
+    let mut days: Vec<(u32, u32, Vec<Part>)> = Vec::new();
+    for (year, day, part) in selected {
+        match days.last_mut() {
+            Some((y, d, parts)) if (*y, *d) == (year, day) => parts.push(part),
+            _ => days.push((year, day, vec![part])),
+        }
+    }
+
+    if selection.input.is_fixed() && days.len() > 1 {
+        return Err("--input and --stdin can only be used with a single day".to_string());
+    }
+
+    println!(
+        "{:<20} {:>12} {:>12} {:>12}",
+        format!("{} iterations", iterations),
+        "min",
+        "median",
+        "max"
+    );
+
+    let mut totals: Vec<(u32, Stats)> = Vec::new();
+    let mut failed = 0;
+    for (year, day, parts) in days {
+        let solution = registry.get(year, day).unwrap();
+        // A missing input or a parse error only skips that day
+        let timings = selection.input.read(year, day).and_then(|contents| {
+            solution
+                .bench(&contents, &parts, iterations)
+                .map_err(|err| err.to_string())
+        });
+        let timings = match timings {
+            Ok(timings) => timings,
+            Err(err) => {
+                failed += 1;
+                eprintln!("{} day {:>2}: {}", year, day, err);
+                continue;
+            }
+        };
+
+        let mut total = timings.parse;
+        print_stats(&format!("{} day {:>2} parse", year, day), &timings.parse);
+        if let Some(stats) = timings.part1 {
+            print_stats(&format!("{} day {:>2} part 1", year, day), &stats);
+            total = total + stats;
+        }
+        if let Some(stats) = timings.part2 {
+            print_stats(&format!("{} day {:>2} part 2", year, day), &stats);
+            total = total + stats;
+        }
+
+        match totals.last_mut() {
+            Some((y, year_total)) if *y == year => *year_total = *year_total + total,
+            _ => totals.push((year, total)),
+        }
+    }
+
+    for (year, total) in totals {
+        print_stats(&format!("{} total", year), &total);
+    }
+
+    if failed > 0 {
+        return Err(format!("{} days failed", failed));
+    }
+    Ok(())
+}
+
+/// Execute a parsed command.
+pub fn execute(registry: &Registry, command: &Command) -> Result<(), String> {
+    match command {
+        Command::Run(selection) => run(registry, selection),
+        Command::RunParallel(selection, threads) => run_parallel(registry, selection, *threads),
+        Command::Verify(selection) => verify(registry, selection),
+        Command::Bench(selection, iterations) => bench(registry, selection, *iterations),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Args, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    fn selection(line: &str) -> Selection {
+        match parse(line).unwrap().command {
+            Command::Run(selection)
+            | Command::RunParallel(selection, _)
+            | Command::Verify(selection)
+            | Command::Bench(selection, _) => selection,
+            Command::Help => panic!("no selection for help"),
+        }
+    }
+
+    #[test]
+    fn day_ranges() {
+        assert_eq!(parse_days("7"), Ok(7..=7));
+        assert_eq!(parse_days("3..10"), Ok(3..=10));
+        assert_eq!(parse_days("3..=10"), Ok(3..=10));
+        assert_eq!(parse_days("5..5"), Ok(5..=5));
+        assert!(parse_days("10..3").is_err());
+        assert!(parse_days("3..").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn verbosity_anywhere() {
+        assert_eq!(parse("-vv run --day 3").unwrap().verbosity, 2);
+        assert_eq!(parse("run -v --day 3 --verbose -vv").unwrap().verbosity, 4);
+        assert_eq!(parse("run --day 3").unwrap().verbosity, 0);
+    }
+
+    #[test]
+    fn input_sources() {
+        assert_eq!(
+            selection("run --day 10 --example 2").input,
+            InputSource::Example(Some("2".to_string()))
+        );
+        let example = selection("run --day 10 --example --part 1");
+        assert_eq!(example.input, InputSource::Example(None));
+        assert_eq!(example.part, Some(Part::One));
+        assert_eq!(selection("run --day 10 --stdin").input, InputSource::Stdin);
+        assert!(parse("verify --day 10 --example").is_err());
+        assert!(parse("verify --day 10 --input path").is_err());
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse("").unwrap().command, Command::Help);
+        assert!(matches!(
+            parse("run --day 1..3 --jobs 4").unwrap().command,
+            Command::RunParallel(_, 4)
+        ));
+        assert!(matches!(
+            parse("bench --all --iterations 3").unwrap().command,
+            Command::Bench(_, 3)
+        ));
+        assert!(selection("verify").all);
+        assert!(parse("run").is_err());
+        assert!(parse("run --day 1 --jobs 0").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("frobnicate").is_err());
+    }
+
+    #[test]
+    fn image_options() {
+        assert_eq!(parse("run --day 1").unwrap().images, None);
+        assert_eq!(
+            parse("run --images out --day 1").unwrap().images,
+            Some(ImageOptions::new(PathBuf::from("out")))
+        );
+        let images = parse("run --day 1 --images out --image-format ppm --cell-size 2")
+            .unwrap()
+            .images
+            .unwrap();
+        assert_eq!((images.format, images.cell_size), (Format::Ppm, 2));
+        assert!(parse("run --day 1 --images out --image-format gif").is_err());
+        assert!(parse("run --day 1 --images out --cell-size 0").is_err());
+        assert!(parse("run --day 1 --cell-size 2").is_err());
+    }
+
+    #[test]
+    fn resolve_selection() {
+        let registry = Registry::new();
+        assert_eq!(
+            selection("run --year 2023 --day 3..4 --part 2").resolve(&registry),
+            Ok(vec![(2023, 3, Part::Two), (2023, 4, Part::Two)])
+        );
+        assert_eq!(
+            selection("run --day 1").resolve(&registry).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            selection("run --year 2023 --day 99").resolve(&registry),
+            Err("no solution for 2023 day 99".to_string())
+        );
+        assert!(selection("run --day 20..30").resolve(&registry).is_err());
+
+        let all = selection("run --all").resolve(&registry).unwrap();
+        assert!(all
+            .iter()
+            .all(|(year, day, _)| InputSource::Default.path(*year, *day).unwrap().exists()));
+    }
+}
@@ -0,0 +1,129 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A cycle in a sequence of states: the state after `start + length` steps
+/// equals the state after `start` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Smallest number of steps that gives the same state as `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Step from `initial` until a state repeats, comparing whole states.
+///
+/// Returns the cycle together with all states up to, but not including,
+/// the first repeated state, so that `states[cycle.reduce(n)]` is the state
+/// after `n` steps.
+pub fn find<T: Clone + Eq + Hash>(initial: T, mut step: impl FnMut(&T) -> T) -> (Cycle, Vec<T>) {
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    let mut states = vec![initial.clone()];
+    seen.insert(initial, 0);
+    loop {
+        let next = step(states.last().unwrap());
+        if let Some(&start) = seen.get(&next) {
+            let length = states.len() - start;
+            return (Cycle { start, length }, states);
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+}
+
+/// The state after `n` steps from `initial`, skipping ahead once a cycle is found.
+pub fn nth<T: Clone + Eq + Hash>(initial: T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    let mut states = vec![initial.clone()];
+    seen.insert(initial, 0);
+    while states.len() <= n {
+        let next = step(states.last().unwrap());
+        if let Some(&start) = seen.get(&next) {
+            let cycle = Cycle {
+                start,
+                length: states.len() - start,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+    states.swap_remove(n)
+}
+
+/// Find the cycle with Brent's algorithm, which only keeps two states in
+/// memory but calls `step` more often than `find`.
+pub fn brent<T: Clone + Eq>(initial: &T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // Find the cycle length by moving the hare ahead in increasing powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Find the start with the hare a cycle length ahead of the tortoise
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, 4, 5, 6, 3, 4, ...
+    fn step(x: &u32) -> u32 {
+        if *x < 6 {
+            x + 1
+        } else {
+            3
+        }
+    }
+
+    #[test]
+    fn find_cycle() {
+        let (cycle, states) = find(0, step);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 3,
+                length: 4
+            }
+        );
+        assert_eq!(states, [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(brent(&0, step), cycle);
+    }
+
+    #[test]
+    fn nth_state() {
+        assert_eq!(nth(0, step, 2), 2);
+        assert_eq!(nth(0, step, 7), 3);
+        assert_eq!(nth(0, step, 1_000_000_001), 5);
+    }
+}
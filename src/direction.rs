@@ -0,0 +1,137 @@
+/// One of the four directions on a grid, in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.turn_left().turn_left()
+    }
+
+    /// New direction after bouncing off a `/` mirror.
+    pub fn reflect_slash(self) -> Direction {
+        match self {
+            Direction::Up | Direction::Down => self.turn_right(),
+            Direction::Left | Direction::Right => self.turn_left(),
+        }
+    }
+
+    /// New direction after bouncing off a `\` mirror.
+    pub fn reflect_backslash(self) -> Direction {
+        match self {
+            Direction::Up | Direction::Down => self.turn_left(),
+            Direction::Left | Direction::Right => self.turn_right(),
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    /// (row, column) offset of one step in this direction.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Right => (0, 1),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+        }
+    }
+}
+
+/// A position on a grid, with the row increasing downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub fn new(row: usize, col: usize) -> Self {
+        Pos { row, col }
+    }
+
+    /// Position one step in direction `dir`, if it is not above or left of
+    /// the origin.
+    pub fn step(self, dir: Direction) -> Option<Pos> {
+        let (d_row, d_col) = dir.offset();
+        Some(Pos {
+            row: self.row.checked_add_signed(d_row)?,
+            col: self.col.checked_add_signed(d_col)?,
+        })
+    }
+
+    /// Position one step in direction `dir`, if it is inside a grid of the
+    /// given size.
+    pub fn step_within(self, dir: Direction, width: usize, height: usize) -> Option<Pos> {
+        self.step(dir)
+            .filter(|pos| pos.row < height && pos.col < width)
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((row, col): (usize, usize)) -> Self {
+        Pos { row, col }
+    }
+}
+
+impl From<Pos> for (usize, usize) {
+    fn from(pos: Pos) -> Self {
+        (pos.row, pos.col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.reverse().reverse(), dir);
+            assert_eq!(dir.reflect_slash().reflect_slash(), dir);
+            assert_eq!(dir.reflect_backslash().reflect_backslash(), dir);
+        }
+        assert_eq!(Direction::Right.reflect_slash(), Direction::Up);
+        assert_eq!(Direction::Right.reflect_backslash(), Direction::Down);
+    }
+
+    #[test]
+    fn stepping() {
+        let pos = Pos::new(0, 1);
+        assert_eq!(pos.step(Direction::Up), None);
+        assert_eq!(pos.step(Direction::Left), Some(Pos::new(0, 0)));
+        assert_eq!(pos.step_within(Direction::Right, 2, 2), None);
+        assert_eq!(pos.step_within(Direction::Down, 2, 2), Some(Pos::new(1, 1)));
+    }
+}
@@ -0,0 +1,278 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    direction::Pos,
+    parsing::{self, Line, ParseError},
+};
+
+/// Offsets of the four neighbours of a cell: up, down, left and right.
+pub const OFFSETS4: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Offsets of the eight neighbours of a cell, row by row.
+pub const OFFSETS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid of cells, stored row by row.
+///
+/// Cells are addressed by (row, column), starting at (0, 0) in the top left
+/// corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid of the given size with all cells set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Grid from a list of rows, or `None` if the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parse a grid from lines of text, mapping each character to a cell.
+    pub fn from_lines<'a>(
+        lines: impl IntoIterator<Item = Line<'a>>,
+        mapping: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut grid = Grid {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        };
+        for line in lines {
+            let row = line.map_chars(line.text(), &mapping)?;
+            if grid.height == 0 {
+                grid.width = row.len();
+            } else if row.len() != grid.width {
+                return Err(line.error(
+                    line.text(),
+                    format!("expected a row of {} characters", grid.width),
+                ));
+            }
+            grid.cells.extend(row);
+            grid.height += 1;
+        }
+        Ok(grid)
+    }
+
+    /// Parse the whole input of a day as a grid, ignoring empty lines.
+    pub fn parse(
+        day: u32,
+        contents: &str,
+        mapping: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        Grid::from_lines(
+            parsing::lines(day, contents).filter(|line| !line.text().is_empty()),
+            mapping,
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    /// Position moved by the (row, column) `offset`, if it is inside the grid.
+    /// Moving in a `Direction` is done with `Pos::step_within`.
+    fn offset(&self, pos: (usize, usize), offset: (isize, isize)) -> Option<(usize, usize)> {
+        let row = pos.0.checked_add_signed(offset.0)?;
+        let col = pos.1.checked_add_signed(offset.1)?;
+        self.contains((row, col)).then_some((row, col))
+    }
+
+    /// Positions of the up to four horizontal and vertical neighbours.
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS4
+            .iter()
+            .filter_map(move |offset| self.offset(pos, *offset))
+    }
+
+    /// Positions of the up to eight neighbours, including diagonals.
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS8
+            .iter()
+            .filter_map(move |offset| self.offset(pos, *offset))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(col < self.width, "column {} out of bounds", col);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// All cells together with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell, row by row, matching `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Grid with rows and columns swapped.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|col| self.column(col).cloned())
+                .collect(),
+        }
+    }
+
+    /// Grid of the same size with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("position {:?} outside of grid", pos))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("position {:?} outside of grid", pos))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        &self[(pos.row, pos.col)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        &mut self[(pos.row, pos.col)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse(0, "abc\ndef\n", Some).unwrap()
+    }
+
+    #[test]
+    fn parse_and_index() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+    }
+
+    #[test]
+    fn parse_ragged_rows() {
+        let err = Grid::parse(0, "abc\nde", Some).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = example();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn transpose() {
+        let grid = example().transpose();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.row(2), ['c', 'f']);
+    }
+}
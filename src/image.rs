@@ -0,0 +1,324 @@
+use std::{
+    cell::Cell,
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::grid::Grid;
+
+/// Colour of a pixel as red, green and blue.
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const GRAY: Rgb = [128, 128, 128];
+pub const RED: Rgb = [220, 50, 47];
+pub const GREEN: Rgb = [80, 200, 80];
+pub const YELLOW: Rgb = [250, 210, 50];
+pub const BLUE: Rgb = [50, 100, 220];
+
+/// File format of saved images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Png,
+    Ppm,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Ppm => "ppm",
+        }
+    }
+}
+
+/// Where and how the solutions save images of their grids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    pub dir: PathBuf,
+    pub format: Format,
+    /// Size of each grid cell in pixels.
+    pub cell_size: usize,
+}
+
+impl ImageOptions {
+    pub fn new(dir: PathBuf) -> Self {
+        ImageOptions {
+            dir,
+            format: Format::default(),
+            cell_size: DEFAULT_CELL_SIZE,
+        }
+    }
+}
+
+pub const DEFAULT_CELL_SIZE: usize = 4;
+
+static OPTIONS: Mutex<Option<ImageOptions>> = Mutex::new(None);
+
+thread_local! {
+    static PAUSED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set where the solutions save images of their grids. Without options no
+/// images are saved.
+pub fn set_options(options: Option<ImageOptions>) {
+    *OPTIONS.lock().unwrap() = options;
+}
+
+pub fn options() -> Option<ImageOptions> {
+    OPTIONS.lock().unwrap().clone()
+}
+
+/// Run `f` without saving any images on this thread, e.g. while timing
+/// the solutions.
+pub fn without_images<R>(f: impl FnOnce() -> R) -> R {
+    let paused = PAUSED.replace(true);
+    let result = f();
+    PAUSED.set(paused);
+    result
+}
+
+/// Save the image from `make_image` as `<name>.png` or `<name>.ppm` in the
+/// image directory, if images are enabled. The image is only created when it
+/// will be saved.
+pub fn save_if_enabled(name: &str, make_image: impl FnOnce() -> Image) {
+    if PAUSED.get() {
+        return;
+    }
+    let Some(ImageOptions { dir, format, .. }) = options() else {
+        return;
+    };
+    let path = dir.join(format!("{}.{}", name, format.extension()));
+    match fs::create_dir_all(&dir).and_then(|_| make_image().save(&path)) {
+        Ok(()) => crate::verbose!(1, "Saved image {}", path.display()),
+        Err(err) => eprintln!("could not save image {}: {}", path.display(), err),
+    }
+}
+
+/// An image with 8 bit RGB pixels, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: Rgb) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// Write the image as a binary PPM (P6) file.
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels.concat())
+    }
+
+    /// Write the image as an uncompressed PNG file.
+    pub fn write_png(&self, mut out: impl Write) -> io::Result<()> {
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bit depth, RGB, default compression, filter and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+        write_png_chunk(&mut out, b"IHDR", &header)?;
+
+        // Every row starts with the filter type, 0 for no filtering
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.concat());
+        }
+        write_png_chunk(&mut out, b"IDAT", &zlib_stored(&raw))?;
+
+        write_png_chunk(&mut out, b"IEND", &[])
+    }
+
+    /// Save the image, as PPM if the extension of `path` is `ppm` and as
+    /// PNG otherwise.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        if path.extension().is_some_and(|ext| ext == "ppm") {
+            self.write_ppm(&mut out)?;
+        } else {
+            self.write_png(&mut out)?;
+        }
+        out.flush()
+    }
+}
+
+fn write_png_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// CRC-32 as used by PNG.
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xffffffff_u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                0xedb88320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in bytes {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// Wrap `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        // An empty final block
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(is_final as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// Draws a grid as an image, with each cell a square of pixels.
+pub struct Exporter<'a, T> {
+    grid: &'a Grid<T>,
+    palette: Box<dyn Fn(&T) -> Rgb + 'a>,
+    overlays: Vec<(HashSet<(usize, usize)>, Rgb)>,
+    cell_size: usize,
+}
+
+impl<'a, T> Exporter<'a, T> {
+    /// Exporter drawing each cell with the colour from `palette`.
+    pub fn new(grid: &'a Grid<T>, palette: impl Fn(&T) -> Rgb + 'a) -> Self {
+        Exporter {
+            grid,
+            palette: Box::new(palette),
+            overlays: Vec::new(),
+            cell_size: options().map_or(DEFAULT_CELL_SIZE, |options| options.cell_size),
+        }
+    }
+
+    /// Size of each cell in pixels (default: the size from the image options,
+    /// or 4).
+    pub fn cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    /// Mix `color` into the given cells. Later overlays are mixed in on top
+    /// of earlier ones.
+    pub fn overlay(
+        mut self,
+        positions: impl IntoIterator<Item = (usize, usize)>,
+        color: Rgb,
+    ) -> Self {
+        self.overlays.push((positions.into_iter().collect(), color));
+        self
+    }
+
+    pub fn to_image(&self) -> Image {
+        let size = self.cell_size;
+        let mut image = Image::new(self.grid.width() * size, self.grid.height() * size, BLACK);
+        for ((row, col), cell) in self.grid.iter() {
+            let mut color = (self.palette)(cell);
+            for (positions, overlay) in &self.overlays {
+                if positions.contains(&(row, col)) {
+                    color = mix(color, *overlay);
+                }
+            }
+            for y in row * size..(row + 1) * size {
+                for x in col * size..(col + 1) * size {
+                    image.set_pixel(x, y, color);
+                }
+            }
+        }
+        image
+    }
+}
+
+/// The average of two colours.
+fn mix(a: Rgb, b: Rgb) -> Rgb {
+    [0, 1, 2].map(|inx| ((a[inx] as u16 + b[inx] as u16) / 2) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn export_grid() {
+        let grid = Grid::parse(0, "#.\n..", Some).unwrap();
+        let image = Exporter::new(&grid, |c| if *c == '#' { WHITE } else { BLACK })
+            .cell_size(2)
+            .overlay([(1, 1)], RED)
+            .to_image();
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.pixel(1, 1), WHITE);
+        assert_eq!(image.pixel(2, 0), BLACK);
+        assert_eq!(image.pixel(3, 3), [110, 25, 23]);
+
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+    }
+}
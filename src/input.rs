@@ -0,0 +1,61 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+/// Where the puzzle input of a solution is read from.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum InputSource {
+    /// The personal puzzle input in `resources/`.
+    #[default]
+    Default,
+    /// An example input in `resources/`, optionally with a suffix such as
+    /// `2` for `day10_example2`.
+    Example(Option<String>),
+    /// Any file.
+    File(PathBuf),
+    /// Standard input.
+    Stdin,
+}
+
+impl InputSource {
+    /// Whether this source always gives the same input, independent of the day.
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, InputSource::File(_) | InputSource::Stdin)
+    }
+
+    /// Path of the input file for the given day, if the input is read from a file.
+    pub fn path(&self, year: u32, day: u32) -> Option<PathBuf> {
+        match self {
+            InputSource::Default => Some(resource_path(year, day, "input")),
+            InputSource::Example(suffix) => Some(resource_path(
+                year,
+                day,
+                &format!("example{}", suffix.as_deref().unwrap_or("")),
+            )),
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+
+    /// Read the input for the given day.
+    pub fn read(&self, year: u32, day: u32) -> Result<String, String> {
+        match self.path(year, day) {
+            Some(path) => fs::read_to_string(&path)
+                .map_err(|err| format!("could not read {}: {}", path.display(), err)),
+            None => {
+                let mut contents = String::new();
+                io::stdin()
+                    .read_to_string(&mut contents)
+                    .map_err(|err| format!("could not read stdin: {}", err))?;
+                Ok(contents)
+            }
+        }
+    }
+}
+
+/// Path to a file in `resources/` for the given day, e.g. `day7_example`.
+pub fn resource_path(_year: u32, day: u32, name: &str) -> PathBuf {
+    PathBuf::from(format!("resources/day{}_{}", day, name))
+}
@@ -0,0 +1,355 @@
+use std::cmp;
+
+/// Half-open interval `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The overlapping part of both intervals, if any.
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let interval = Interval::new(
+            cmp::max(self.start, other.start),
+            cmp::min(self.end, other.end),
+        );
+        (!interval.is_empty()).then_some(interval)
+    }
+}
+
+impl Interval<u64> {
+    /// Interval of `length` values starting at `start`.
+    ///
+    /// Panics if the end does not fit in a `u64`, see `checked_with_length`.
+    pub fn with_length(start: u64, length: u64) -> Self {
+        Interval::checked_with_length(start, length).expect("interval end overflows")
+    }
+
+    /// Interval of `length` values starting at `start`, or `None` if the end
+    /// does not fit in a `u64`.
+    pub fn checked_with_length(start: u64, length: u64) -> Option<Self> {
+        Some(Interval::new(start, start.checked_add(length)?))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// A set of values, stored as sorted intervals that neither overlap nor touch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn from_intervals(intervals: impl IntoIterator<Item = Interval<T>>) -> Self {
+        let mut intervals = intervals
+            .into_iter()
+            .filter(|interval| !interval.is_empty())
+            .collect::<Vec<Interval<T>>>();
+        intervals.sort();
+
+        // Merge overlapping and touching intervals
+        let mut merged: Vec<Interval<T>> = Vec::new();
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => {
+                    last.end = cmp::max(last.end, interval.end);
+                }
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.intervals
+            .iter()
+            .any(|interval| interval.contains(value))
+    }
+
+    /// Smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        *self = IntervalSet::from_intervals(self.intervals.iter().copied().chain([interval]));
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        IntervalSet::from_intervals(self.intervals.iter().chain(&other.intervals).copied())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut inx, mut other_inx) = (0, 0);
+        while inx < self.intervals.len() && other_inx < other.intervals.len() {
+            let (a, b) = (&self.intervals[inx], &other.intervals[other_inx]);
+            intervals.extend(a.intersection(b));
+            // Move past the interval that ends first
+            if a.end < b.end {
+                inx += 1;
+            } else {
+                other_inx += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let mut other_inx = 0;
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            // Skip the intervals of other that end before this interval
+            while other_inx < other.intervals.len() && other.intervals[other_inx].end <= start {
+                other_inx += 1;
+            }
+            let mut inx = other_inx;
+            while inx < other.intervals.len() && other.intervals[inx].start < interval.end {
+                let removed = &other.intervals[inx];
+                if start < removed.start {
+                    intervals.push(Interval::new(start, removed.start));
+                }
+                start = cmp::max(start, removed.end);
+                inx += 1;
+            }
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+/// A mapping of `u64` values that moves ranges of source values to ranges of
+/// destination values, and maps all other values to themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap {
+    /// Source intervals with the destination of their start, sorted by source.
+    entries: Vec<(Interval<u64>, u64)>,
+}
+
+impl RangeMap {
+    pub fn new() -> Self {
+        RangeMap {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Map the values in `source` to the values starting at `dest_start`.
+    ///
+    /// Returns false, leaving the map unchanged, if `source` overlaps a range
+    /// that is already mapped or the destination does not fit in a `u64`.
+    pub fn insert(&mut self, source: Interval<u64>, dest_start: u64) -> bool {
+        if dest_start.checked_add(source.len()).is_none() {
+            return false;
+        }
+        let inx = self
+            .entries
+            .partition_point(|(entry, _)| entry.start < source.start);
+        let overlaps_prev = inx > 0 && self.entries[inx - 1].0.end > source.start;
+        let overlaps_next = inx < self.entries.len() && self.entries[inx].0.start < source.end;
+        if overlaps_prev || overlaps_next {
+            return false;
+        }
+        if !source.is_empty() {
+            self.entries.insert(inx, (source, dest_start));
+        }
+        true
+    }
+
+    pub fn get(&self, value: u64) -> u64 {
+        match self
+            .entries
+            .iter()
+            .find(|(source, _)| source.contains(value))
+        {
+            Some((source, dest_start)) => dest_start + (value - source.start),
+            None => value,
+        }
+    }
+
+    /// All source intervals covering `0..u64::MAX`, including the ones that
+    /// map to themselves, with the destination of their start.
+    fn segments(&self) -> Vec<(Interval<u64>, u64)> {
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for (source, dest_start) in &self.entries {
+            if start < source.start {
+                segments.push((Interval::new(start, source.start), start));
+            }
+            segments.push((*source, *dest_start));
+            start = source.end;
+        }
+        if start < u64::MAX {
+            segments.push((Interval::new(start, u64::MAX), start));
+        }
+        segments
+    }
+
+    /// The image of a set of values.
+    pub fn map_set(&self, set: &IntervalSet<u64>) -> IntervalSet<u64> {
+        let segments = self.segments();
+        IntervalSet::from_intervals(set.intervals().iter().flat_map(|interval| {
+            segments.iter().filter_map(|(source, dest_start)| {
+                let part = source.intersection(interval)?;
+                Some(Interval::with_length(
+                    dest_start + (part.start - source.start),
+                    part.len(),
+                ))
+            })
+        }))
+    }
+
+    /// The map that first applies this map and then `then`.
+    pub fn compose(&self, then: &RangeMap) -> RangeMap {
+        let then_segments = then.segments();
+        let mut composed = RangeMap::new();
+        for (source, dest_start) in self.segments() {
+            let image = Interval::with_length(dest_start, source.len());
+            for (then_source, then_dest_start) in &then_segments {
+                let Some(part) = image.intersection(then_source) else {
+                    continue;
+                };
+                let part_source_start = source.start + (part.start - image.start);
+                let part_dest_start = then_dest_start + (part.start - then_source.start);
+                if part_source_start != part_dest_start {
+                    composed.insert(
+                        Interval::with_length(part_source_start, part.len()),
+                        part_dest_start,
+                    );
+                }
+            }
+        }
+        composed
+    }
+
+    /// The map that undoes this map, or `None` if several values map to the
+    /// same value.
+    pub fn invert(&self) -> Option<RangeMap> {
+        let mut images = self
+            .segments()
+            .into_iter()
+            .map(|(source, dest_start)| {
+                (
+                    Interval::with_length(dest_start, source.len()),
+                    source.start,
+                )
+            })
+            .collect::<Vec<(Interval<u64>, u64)>>();
+        images.sort();
+
+        let mut inverted = RangeMap::new();
+        for (image, source_start) in images {
+            if !inverted.insert(image, source_start) {
+                return None;
+            }
+        }
+        inverted
+            .entries
+            .retain(|(image, source_start)| image.start != *source_start);
+        Some(inverted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(u64, u64)]) -> IntervalSet<u64> {
+        IntervalSet::from_intervals(
+            intervals
+                .iter()
+                .map(|(start, end)| Interval::new(*start, *end)),
+        )
+    }
+
+    #[test]
+    fn checked_length() {
+        assert_eq!(
+            Interval::checked_with_length(5, 3),
+            Some(Interval::new(5, 8))
+        );
+        assert_eq!(Interval::checked_with_length(u64::MAX - 1, 2), None);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 5), (3, 8), (10, 12)]);
+        let b = set(&[(4, 11), (20, 21)]);
+        assert_eq!(a, set(&[(0, 8), (10, 12)]));
+        assert_eq!(a.union(&b), set(&[(0, 12), (20, 21)]));
+        assert_eq!(a.intersection(&b), set(&[(4, 8), (10, 11)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (11, 12)]));
+        assert_eq!(b.difference(&a), set(&[(8, 10), (20, 21)]));
+    }
+
+    fn example_map() -> RangeMap {
+        // The seed-to-soil map of the day 5 example
+        let mut map = RangeMap::new();
+        assert!(map.insert(Interval::with_length(98, 2), 50));
+        assert!(map.insert(Interval::with_length(50, 48), 52));
+        assert!(!map.insert(Interval::with_length(90, 10), 0));
+        map
+    }
+
+    #[test]
+    fn map_values_and_sets() {
+        let map = example_map();
+        assert_eq!(
+            [79, 14, 55, 13, 98].map(|x| map.get(x)),
+            [81, 14, 57, 13, 50]
+        );
+        assert_eq!(
+            map.map_set(&set(&[(45, 55), (97, 101)])),
+            set(&[(45, 57), (99, 101)])
+        );
+    }
+
+    #[test]
+    fn compose_and_invert() {
+        let map = example_map();
+        let inverse = map.invert().unwrap();
+        let identity = map.compose(&inverse);
+        let twice = map.compose(&map);
+        for x in 0..120 {
+            assert_eq!(inverse.get(map.get(x)), x);
+            assert_eq!(identity.get(x), x);
+            assert_eq!(twice.get(x), map.get(map.get(x)));
+        }
+
+        let mut collapsing = RangeMap::new();
+        collapsing.insert(Interval::with_length(0, 2), 10);
+        assert_eq!(collapsing.invert(), None);
+    }
+}
@@ -0,0 +1,26 @@
+//! Solutions to the puzzles on [adventofcode.com](https://adventofcode.com),
+//! together with the shared code for parsing inputs and running solutions.
+//!
+//! Every day implements [`solution::Solution`], and all days are collected in
+//! the [`registry::Registry`].
+
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod cycle;
+pub mod direction;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod parallel;
+pub mod parsing;
+pub mod registry;
+pub mod render;
+pub mod rng;
+pub mod search;
+pub mod solution;
+pub mod verbosity;
+pub mod year2023;
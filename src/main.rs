@@ -1,6 +1,23 @@
-mod year2023;
-use crate::year2023::*;
+use std::{env, process};
+
+use adevent_of_code::{cli, image, registry::Registry, verbosity};
 
 fn main() {
-    day16::problem2();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match cli::parse_args(&args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    verbosity::set_verbosity(args.verbosity);
+    image::set_options(args.images.clone());
+
+    let registry = Registry::new();
+    if let Err(message) = cli::execute(&registry, &args.command) {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,170 @@
+use std::ops::RangeInclusive;
+
+use num::{
+    integer::{Integer, Roots},
+    BigInt, Signed, ToPrimitive, Zero,
+};
+
+/// Greatest common divisor of all values, zero if there are none.
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values
+        .into_iter()
+        .fold(T::zero(), |acc, value| acc.gcd(&value))
+}
+
+/// Least common multiple of all values, one if there are none.
+///
+/// Use `checked_lcm_all` or big integers when the result might overflow.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values
+        .into_iter()
+        .fold(T::one(), |acc, value| acc.lcm(&value))
+}
+
+/// Least common multiple of all values, or `None` if it does not fit in a `u64`.
+pub fn checked_lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1_u64, |acc, value| {
+        if value == 0 {
+            return Some(0);
+        }
+        (acc / acc.gcd(&value)).checked_mul(value)
+    })
+}
+
+/// Combine `x = r1 (mod m1)` and `x = r2 (mod m2)` into a single congruence,
+/// with the residue in `0..modulus`. The moduli need not be coprime.
+fn combine<T: Integer + Signed + Clone>((r1, m1): (T, T), (r2, m2): (T, T)) -> Option<(T, T)> {
+    let gcd = m1.extended_gcd(&m2);
+    let diff = r2 - r1.clone();
+    if !(diff.clone() % gcd.gcd.clone()).is_zero() {
+        return None;
+    }
+    // Solve m1 * k = diff (mod m2) for k, using m1 * gcd.x = gcd (mod m2)
+    let m2_reduced = m2 / gcd.gcd.clone();
+    let k = (diff / gcd.gcd.clone()).mod_floor(&m2_reduced) * gcd.x.mod_floor(&m2_reduced);
+    let modulus = m1.clone() * m2_reduced.clone();
+    let residue = (r1 + m1 * k.mod_floor(&m2_reduced)).mod_floor(&modulus);
+    Some((residue, modulus))
+}
+
+/// Chinese Remainder Theorem: the solutions of all congruences
+/// `x = residue (mod modulus)` as a single `(residue, modulus)`, with the
+/// residue in `0..modulus`.
+///
+/// Returns `None` if the congruences have no common solution or the
+/// combined modulus does not fit in an `i64`. The moduli must be positive.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    // The intermediate products of two i64 values always fit in an i128
+    let mut result = (0_i128, 1_i128);
+    for (residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive");
+        result = combine(result, (residue as i128, modulus as i128))?;
+        if result.1 > i64::MAX as i128 {
+            return None;
+        }
+    }
+    Some((result.0 as i64, result.1 as i64))
+}
+
+/// Chinese Remainder Theorem for big integers, see `crt`.
+pub fn crt_big(
+    congruences: impl IntoIterator<Item = (BigInt, BigInt)>,
+) -> Option<(BigInt, BigInt)> {
+    congruences
+        .into_iter()
+        .try_fold((BigInt::zero(), BigInt::from(1)), |acc, congruence| {
+            assert!(congruence.1.is_positive(), "modulus must be positive");
+            combine(acc, congruence)
+        })
+}
+
+/// Largest integer whose square is at most `n`, computed without floating
+/// point rounding.
+pub fn isqrt<T: Roots>(n: &T) -> T {
+    n.sqrt()
+}
+
+/// The square root of `n`, if `n` is a perfect square.
+pub fn exact_sqrt<T: Roots + Clone>(n: &T) -> Option<T> {
+    let root = n.sqrt();
+    (root.clone() * root.clone() == *n).then_some(root)
+}
+
+/// The integers `x` for which `a * x^2 + b * x + c < 0`, as an inclusive
+/// `(first, last)` pair, or `None` if there are none. `a` must be positive.
+pub fn quadratic_negative_big(a: &BigInt, b: &BigInt, c: &BigInt) -> Option<(BigInt, BigInt)> {
+    assert!(a.is_positive(), "leading coefficient must be positive");
+    let discriminant: BigInt = b * b - 4 * a * c;
+    if !discriminant.is_positive() {
+        return None;
+    }
+    let root = isqrt(&discriminant);
+    let f = |x: &BigInt| (a * x + b) * x + c;
+
+    // The roots lie in ((-b - root - 1) / 2a, (-b + root + 1) / 2a), so
+    // start just outside and move inwards
+    let two_a: BigInt = 2 * a;
+    let mut first = Integer::div_floor(&(-b - &root - 1), &two_a);
+    let mut last = Integer::div_ceil(&(-b + &root + 1), &two_a);
+    while first <= last && !f(&first).is_negative() {
+        first += 1;
+    }
+    while last >= first && !f(&last).is_negative() {
+        last -= 1;
+    }
+    (first <= last).then_some((first, last))
+}
+
+/// The integers `x` for which `a * x^2 + b * x + c < 0`, see
+/// `quadratic_negative_big`. The intermediate values are computed with big
+/// integers, so this never overflows; `None` is also returned if the
+/// integers do not fit in an `i64`.
+pub fn quadratic_negative(a: i64, b: i64, c: i64) -> Option<RangeInclusive<i64>> {
+    let (first, last) = quadratic_negative_big(&a.into(), &b.into(), &c.into())?;
+    Some(first.to_i64()?..=last.to_i64()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        assert_eq!(lcm_all([4, 6, 10]), 60);
+        assert_eq!(lcm_all(Vec::<u64>::new()), 1);
+        assert_eq!(checked_lcm_all([4, 6, 10]), Some(60));
+        assert_eq!(checked_lcm_all([u64::MAX, 2]), None);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Moduli that are not coprime
+        assert_eq!(crt([(3, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt([(0, 4), (1, 6)]), None);
+        assert_eq!(crt([(0, i64::MAX), (0, 2)]), None);
+        assert_eq!(
+            crt_big([
+                (BigInt::from(0), BigInt::from(i64::MAX)),
+                (1.into(), 2.into())
+            ]),
+            Some((BigInt::from(i64::MAX), BigInt::from(i64::MAX) * 2))
+        );
+    }
+
+    #[test]
+    fn roots() {
+        assert_eq!(isqrt(&24_u64), 4);
+        assert_eq!(isqrt(&u64::MAX), u32::MAX as u64);
+        assert_eq!(exact_sqrt(&25_u64), Some(5));
+        assert_eq!(exact_sqrt(&26_u64), None);
+
+        // x * (7 - x) > 9, the first race of day 6
+        assert_eq!(quadratic_negative(1, -7, 9), Some(2..=5));
+        // x * (30 - x) > 200 touches the record at 10 and 20
+        assert_eq!(quadratic_negative(1, -30, 200), Some(11..=19));
+        assert_eq!(quadratic_negative(1, 0, 0), None);
+        assert_eq!(quadratic_negative(4, -2, 0), None);
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{answer::Answer, parsing::ParseError, solution::Part, solution::Runnable};
+
+/// One part of a solution to run, together with its input.
+#[derive(Clone)]
+pub struct Job {
+    pub year: u32,
+    pub day: u32,
+    pub part: Part,
+    pub solution: &'static dyn Runnable,
+    pub contents: Arc<str>,
+}
+
+/// Why a solution did not give an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Input(String),
+    Parse(ParseError),
+    Panic(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Input(message) => write!(f, "{}", message),
+            Failure::Parse(err) => write!(f, "{}", err),
+            Failure::Panic(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+/// Outcome of running one part of a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub year: u32,
+    pub day: u32,
+    pub part: Part,
+    pub result: Result<Answer, Failure>,
+    pub time: Duration,
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn run_job(job: &Job) -> Report {
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        job.solution.run(&job.contents, job.part)
+    }));
+    let time = start.elapsed();
+
+    let result = match result {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(err)) => Err(Failure::Parse(err)),
+        Err(payload) => Err(Failure::Panic(panic_message(payload.as_ref()))),
+    };
+    Report {
+        year: job.year,
+        day: job.day,
+        part: job.part,
+        result,
+        time,
+    }
+}
+
+thread_local! {
+    /// Whether panics on this thread are reported with the results instead
+    /// of by the panic hook.
+    static SILENT_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Install a panic hook, once per process, that keeps the worker threads
+/// quiet and leaves the panic messages of all other threads as they were.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENT_PANICS.with(|silent| silent.get()) {
+                hook(info);
+            }
+        }));
+    });
+}
+
+/// Number of threads to use when none is given.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Run all jobs on a pool of `threads` threads. A panic in one solution is
+/// reported as a failure of that job and does not affect the others.
+///
+/// The reports are returned in the same order as the jobs.
+pub fn run(jobs: &[Job], threads: usize) -> Vec<Report> {
+    install_panic_hook();
+
+    let next_inx = AtomicUsize::new(0);
+    let reports: Mutex<Vec<(usize, Report)>> = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, jobs.len().max(1)) {
+            scope.spawn(|| {
+                // The panics are reported with the results
+                SILENT_PANICS.with(|silent| silent.set(true));
+                loop {
+                    let inx = next_inx.fetch_add(1, Ordering::Relaxed);
+                    let Some(job) = jobs.get(inx) else {
+                        break;
+                    };
+                    let report = run_job(job);
+                    reports.lock().unwrap().push((inx, report));
+                }
+            });
+        }
+    });
+
+    let mut reports = reports.into_inner().unwrap();
+    reports.sort_by_key(|(inx, _)| *inx);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solution;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i64>;
+
+        fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+            crate::parsing::lines(0, contents)
+                .map(|line| line.parse(line.text()))
+                .collect()
+        }
+
+        fn part1(input: &Self::Input) -> Answer {
+            input.iter().sum::<i64>().into()
+        }
+
+        fn part2(_input: &Self::Input) -> Answer {
+            panic!("not implemented")
+        }
+    }
+
+    fn job(part: Part, contents: &str) -> Job {
+        Job {
+            year: 2023,
+            day: 1,
+            part,
+            solution: &Sum,
+            contents: Arc::from(contents),
+        }
+    }
+
+    #[test]
+    fn failures_are_isolated() {
+        let jobs = [
+            job(Part::One, "1\n2\n3"),
+            job(Part::Two, "1\n2\n3"),
+            job(Part::One, "1\nx"),
+            job(Part::One, "4"),
+        ];
+        let results = run(&jobs, 3)
+            .into_iter()
+            .map(|report| report.result)
+            .collect::<Vec<_>>();
+
+        assert_eq!(results[0], Ok(Answer::from(6)));
+        assert_eq!(
+            results[1],
+            Err(Failure::Panic("not implemented".to_string()))
+        );
+        assert!(matches!(results[2], Err(Failure::Parse(_))));
+        assert_eq!(results[3], Ok(Answer::from(4)));
+    }
+}
@@ -0,0 +1,318 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// Error from parsing a puzzle input, pointing at the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number in characters, starting at 1.
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(
+        day: u32,
+        line: usize,
+        column: usize,
+        text: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        ParseError {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}: '{}'",
+            self.day, self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// A single line of a puzzle input, used to create errors that point at
+/// the part of the line that could not be parsed.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    day: u32,
+    inx: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Character index in this line where `part` starts, or 0 if `part` is
+    /// not a substring of this line.
+    fn col_inx_of(&self, part: &str) -> usize {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|offset| self.text.is_char_boundary(*offset))
+            .unwrap_or(0);
+        self.text[..offset].chars().count()
+    }
+
+    /// Error about `part`, which should be a substring of this line.
+    pub fn error(&self, part: &str, message: impl Into<String>) -> ParseError {
+        self.error_at(self.col_inx_of(part), part, message)
+    }
+
+    /// Error about `part`, which starts at character index `col_inx` of this line.
+    pub fn error_at(&self, col_inx: usize, part: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.day, self.inx + 1, col_inx + 1, part, message)
+    }
+
+    /// Parse `part` of this line, ignoring surrounding whitespace.
+    pub fn parse<T: FromStr>(&self, part: &str) -> Result<T, ParseError> {
+        let part = part.trim();
+        part.parse::<T>()
+            .map_err(|_| self.error(part, format!("expected {}", type_name::<T>())))
+    }
+
+    /// Split `part` of this line at the first occurrence of `delimiter`.
+    pub fn split_once(
+        &self,
+        part: &'a str,
+        delimiter: char,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        part.split_once(delimiter)
+            .ok_or_else(|| self.error(part, format!("expected '{}'", delimiter)))
+    }
+
+    /// Parse all whitespace separated values in `part` of this line.
+    pub fn parse_all<T: FromStr>(&self, part: &str) -> Result<Vec<T>, ParseError> {
+        part.split_whitespace().map(|n| self.parse(n)).collect()
+    }
+
+    /// Map every character in `part` of this line, failing on the first
+    /// character the mapping does not accept.
+    pub fn map_chars<T>(
+        &self,
+        part: &str,
+        mapping: impl Fn(char) -> Option<T>,
+    ) -> Result<Vec<T>, ParseError> {
+        let first_col_inx = self.col_inx_of(part);
+        part.chars()
+            .enumerate()
+            .map(|(col_inx, c)| {
+                mapping(c).ok_or_else(|| {
+                    self.error_at(
+                        first_col_inx + col_inx,
+                        &c.to_string(),
+                        "unexpected character",
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Parse all integers in `part` of this line, including a leading minus
+    /// sign, ignoring any other text around them.
+    pub fn ints<T: FromStr>(&self, part: &str) -> Result<Vec<T>, ParseError> {
+        let mut numbers = Vec::new();
+        let mut start = None;
+        for (inx, c) in part.char_indices() {
+            let is_minus = c == '-'
+                && part[inx + 1..]
+                    .chars()
+                    .next()
+                    .is_some_and(|next| next.is_ascii_digit());
+            match start {
+                Some(_) if c.is_ascii_digit() => (),
+                Some(first) => {
+                    numbers.push(self.parse(&part[first..inx])?);
+                    start = is_minus.then_some(inx);
+                }
+                None if c.is_ascii_digit() || is_minus => start = Some(inx),
+                None => (),
+            }
+        }
+        if let Some(first) = start {
+            numbers.push(self.parse(&part[first..])?);
+        }
+        Ok(numbers)
+    }
+
+    /// Parse a `key: values` record, with whitespace separated values.
+    pub fn key_values<T: FromStr>(&self, part: &'a str) -> Result<(&'a str, Vec<T>), ParseError> {
+        let (key, values) = self.split_once(part, ':')?;
+        Ok((key.trim(), self.parse_all(values)?))
+    }
+
+    /// Split `part` of this line according to a template such as
+    /// `"{} = ({}, {})"`, returning the text matched by each `{}`.
+    ///
+    /// A capture ends at the first occurrence of the text that follows it in
+    /// the template, so captures must be separated by some text.
+    pub fn captures(&self, part: &'a str, template: &str) -> Result<Vec<&'a str>, ParseError> {
+        let mut literals = template.split("{}");
+        let first = literals.next().unwrap_or("");
+        let mut rest = part
+            .strip_prefix(first)
+            .ok_or_else(|| self.error(part, format!("expected '{}'", first)))?;
+
+        let mut captures = Vec::new();
+        for literal in literals {
+            assert!(
+                !literal.is_empty() || captures.len() + 1 == template.matches("{}").count(),
+                "captures in template '{}' must be separated",
+                template
+            );
+            if literal.is_empty() {
+                captures.push(rest);
+                rest = &rest[rest.len()..];
+            } else {
+                let end = rest
+                    .find(literal)
+                    .ok_or_else(|| self.error(rest, format!("expected '{}'", literal)))?;
+                captures.push(&rest[..end]);
+                rest = &rest[end + literal.len()..];
+            }
+        }
+
+        if !rest.is_empty() {
+            return Err(self.error(rest, "unexpected text"));
+        }
+        Ok(captures)
+    }
+
+    /// Parse `part` of this line according to a template such as
+    /// `"{} = ({}, {})"`, converting each capture to the type in the tuple.
+    pub fn scan<T: Captures<'a>>(&self, part: &'a str, template: &str) -> Result<T, ParseError> {
+        T::from_captures(self, &self.captures(part, template)?)
+    }
+}
+
+/// A value that can be captured by `Line::scan`, ignoring surrounding
+/// whitespace.
+pub trait Capture<'a>: Sized {
+    fn from_capture(line: &Line<'a>, text: &'a str) -> Result<Self, ParseError>;
+}
+
+impl<'a> Capture<'a> for &'a str {
+    fn from_capture(_line: &Line<'a>, text: &'a str) -> Result<Self, ParseError> {
+        Ok(text.trim())
+    }
+}
+
+macro_rules! impl_capture {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> Capture<'a> for $t {
+                fn from_capture(line: &Line<'a>, text: &'a str) -> Result<Self, ParseError> {
+                    line.parse(text)
+                }
+            }
+        )*
+    };
+}
+
+impl_capture!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, char, String);
+
+/// A tuple of values that can be captured by `Line::scan`.
+pub trait Captures<'a>: Sized {
+    fn from_captures(line: &Line<'a>, captures: &[&'a str]) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_captures {
+    ($count:literal, $($t:ident),*) => {
+        impl<'a, $($t: Capture<'a>),*> Captures<'a> for ($($t,)*) {
+            fn from_captures(line: &Line<'a>, captures: &[&'a str]) -> Result<Self, ParseError> {
+                assert_eq!(captures.len(), $count, "wrong number of captures in template");
+                let mut captures = captures.iter();
+                Ok(($($t::from_capture(line, captures.next().unwrap())?,)*))
+            }
+        }
+    };
+}
+
+impl_captures!(1, A);
+impl_captures!(2, A, B);
+impl_captures!(3, A, B, C);
+impl_captures!(4, A, B, C, D);
+
+/// Human readable name of a type, for error messages.
+fn type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Iterate over the lines of the input of the given day.
+pub fn lines(day: u32, contents: &str) -> impl Iterator<Item = Line<'_>> {
+    contents
+        .lines()
+        .enumerate()
+        .map(move |(inx, text)| Line { day, inx, text })
+}
+
+/// Split the input of the given day into blocks of lines separated by one
+/// or more empty lines.
+pub fn paragraphs(day: u32, contents: &str) -> Vec<Vec<Line<'_>>> {
+    let mut paragraphs: Vec<Vec<Line>> = Vec::new();
+    let mut current: Vec<Line> = Vec::new();
+    for line in lines(day, contents) {
+        if line.text().trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> Line<'_> {
+        lines(0, text).next().unwrap()
+    }
+
+    #[test]
+    fn ints() {
+        let line = line("x=-12, y=7..-3 z-4");
+        assert_eq!(line.ints::<i64>(line.text()), Ok(vec![-12, 7, -3, -4]));
+        let err = line.ints::<u32>(line.text()).unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (3, "-12"));
+    }
+
+    #[test]
+    fn scan() {
+        let line = line("AAA = (BBB, CCC)");
+        let (from, left, right): (&str, String, &str) =
+            line.scan(line.text(), "{} = ({}, {})").unwrap();
+        assert_eq!((from, left.as_str(), right), ("AAA", "BBB", "CCC"));
+
+        let err = line
+            .scan::<(&str, &str)>(line.text(), "{} = [{}]")
+            .unwrap_err();
+        assert_eq!((err.column, err.message.as_str()), (1, "expected ' = ['"));
+    }
+
+    #[test]
+    fn paragraphs_and_key_values() {
+        let blocks = paragraphs(0, "a: 1 2\n\n\nb: 3\nc: 4\n");
+        assert_eq!(blocks.iter().map(Vec::len).collect::<Vec<_>>(), [1, 2]);
+        let line = blocks[0][0];
+        assert_eq!(line.key_values::<u32>(line.text()), Ok(("a", vec![1, 2])));
+    }
+}
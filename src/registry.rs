@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use crate::{solution::Runnable, year2023};
+
+/// All known solutions, keyed by (year, day).
+pub struct Registry {
+    solutions: BTreeMap<(u32, u32), &'static dyn Runnable>,
+}
+
+impl Registry {
+    /// Registry with all solutions in this crate.
+    pub fn new() -> Self {
+        let mut registry = Registry {
+            solutions: BTreeMap::new(),
+        };
+        year2023::register(&mut registry);
+        registry
+    }
+
+    /// Register a solution, replacing any earlier solution for the same day.
+    pub fn register(&mut self, year: u32, day: u32, solution: &'static dyn Runnable) {
+        self.solutions.insert((year, day), solution);
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Option<&'static dyn Runnable> {
+        self.solutions.get(&(year, day)).copied()
+    }
+
+    /// All registered (year, day) pairs, in order.
+    pub fn keys(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.solutions.keys().copied()
+    }
+
+    pub fn latest_year(&self) -> Option<u32> {
+        self.solutions.keys().next_back().map(|(year, _)| *year)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::HashSet,
+    env,
+    fmt::Write,
+    io::{self, IsTerminal},
+};
+
+use crate::grid::Grid;
+
+/// Terminal colours, used both for text and for highlighted backgrounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+impl Color {
+    /// ANSI code of the colour as a text colour, or `None` for the default.
+    fn code(self) -> Option<u8> {
+        match self {
+            Color::Default => None,
+            Color::Red => Some(31),
+            Color::Green => Some(32),
+            Color::Yellow => Some(33),
+            Color::Blue => Some(34),
+            Color::Magenta => Some(35),
+            Color::Cyan => Some(36),
+            Color::Gray => Some(90),
+        }
+    }
+}
+
+/// Whether rendered grids should use colours. The grids are printed to
+/// stderr together with the other diagnostics, so colours are only used if
+/// that is a terminal and `NO_COLOR` is not set.
+pub fn use_color() -> bool {
+    env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal()
+}
+
+/// Character and colour to draw a cell with.
+type Style<'a, T> = Box<dyn Fn(&T) -> (char, Color) + 'a>;
+
+/// Draws a grid as text, one character per cell.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    style: Style<'a, T>,
+    highlights: Vec<(HashSet<(usize, usize)>, Color)>,
+    color: bool,
+}
+
+impl<'a, T> Renderer<'a, T> {
+    /// Renderer drawing each cell with the character and colour from `style`.
+    pub fn new(grid: &'a Grid<T>, style: impl Fn(&T) -> (char, Color) + 'a) -> Self {
+        Renderer {
+            grid,
+            style: Box::new(style),
+            highlights: Vec::new(),
+            color: use_color(),
+        }
+    }
+
+    /// Draw the given cells on a background of `color`. Later highlights are
+    /// drawn on top of earlier ones.
+    pub fn highlight(
+        mut self,
+        positions: impl IntoIterator<Item = (usize, usize)>,
+        color: Color,
+    ) -> Self {
+        self.highlights
+            .push((positions.into_iter().collect(), color));
+        self
+    }
+
+    /// Turn colours on or off. Without colours the highlights are not shown.
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for row in 0..self.grid.height() {
+            for col in 0..self.grid.width() {
+                let (c, color) = (self.style)(&self.grid[(row, col)]);
+                let background = self
+                    .highlights
+                    .iter()
+                    .rev()
+                    .find(|(positions, _)| positions.contains(&(row, col)))
+                    .and_then(|(_, color)| color.code());
+
+                let codes = [color.code(), background.map(|code| code + 10)]
+                    .into_iter()
+                    .flatten()
+                    .map(|code| code.to_string())
+                    .collect::<Vec<String>>();
+                if self.color && !codes.is_empty() {
+                    write!(out, "\x1b[{}m{}\x1b[0m", codes.join(";"), c).unwrap();
+                } else {
+                    out.push(c);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Print the grid to stderr, followed by an empty line.
+    pub fn print(&self) {
+        eprintln!("{}", self.render());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_with_and_without_color() {
+        let grid = Grid::parse(0, "#.\n.#", Some).unwrap();
+        let style = |c: &char| match c {
+            '#' => ('#', Color::Yellow),
+            _ => (*c, Color::Default),
+        };
+
+        let plain = Renderer::new(&grid, style).highlight([(0, 1)], Color::Red);
+        assert_eq!(plain.color(false).render(), "#.\n.#\n");
+
+        let colored = Renderer::new(&grid, style)
+            .highlight([(0, 1)], Color::Red)
+            .color(true);
+        assert_eq!(
+            colored.render(),
+            "\x1b[33m#\x1b[0m\x1b[41m.\x1b[0m\n.\x1b[33m#\x1b[0m\n"
+        );
+    }
+}
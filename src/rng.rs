@@ -0,0 +1,52 @@
+/// Small deterministic pseudo random number generator (SplitMix64). Good
+/// enough for sampling, not for anything that needs to be unpredictable.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed value in `0..bound`.
+    pub fn below(&mut self, bound: u128) -> u128 {
+        assert!(bound > 0, "bound must be positive");
+        // Reject the values above the largest multiple of bound to avoid bias
+        let zone = (u128::MAX / bound) * bound;
+        loop {
+            let value = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_and_bounded() {
+        let mut rng = Rng::new(1234567);
+        // Reference values of SplitMix64
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+
+        let mut counts = [0; 3];
+        for _ in 0..3000 {
+            counts[rng.below(3) as usize] += 1;
+        }
+        assert!(counts.iter().all(|count| (900..1100).contains(count)));
+    }
+}
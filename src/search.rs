@@ -0,0 +1,271 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// The states reached by a search, with the cost of reaching them and the
+/// state each was reached from.
+#[derive(Debug, Clone)]
+pub struct Search<S, C> {
+    costs: HashMap<S, C>,
+    parents: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Search<S, C> {
+    fn new() -> Self {
+        Search {
+            costs: HashMap::new(),
+            parents: HashMap::new(),
+        }
+    }
+
+    /// Lowest cost of reaching `state`, if it was reached.
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+
+    pub fn contains(&self, state: &S) -> bool {
+        self.costs.contains_key(state)
+    }
+
+    /// All reached states with their costs, in no particular order.
+    pub fn visited(&self) -> impl Iterator<Item = (&S, C)> + '_ {
+        self.costs.iter().map(|(state, cost)| (state, *cost))
+    }
+
+    /// The states on a cheapest path from a start state to `goal`, including both.
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        if !self.contains(goal) {
+            return None;
+        }
+        let mut path = vec![goal.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth first search from the start states, where every step costs one.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !search.contains(&start) {
+            search.costs.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(state) = queue.pop_front() {
+        let cost = search.costs[&state] + 1;
+        for next in neighbours(&state) {
+            if let Entry::Vacant(entry) = search.costs.entry(next.clone()) {
+                entry.insert(cost);
+                search.parents.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Visit every state reachable from the start states, without tracking
+/// costs or paths. `visit` is called for every state found and returns
+/// whether it is new, so the caller chooses how visited states are stored.
+pub fn traverse<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut visit: impl FnMut(&S) -> bool,
+) where
+    I: IntoIterator<Item = S>,
+{
+    let mut stack: Vec<S> = starts.into_iter().filter(|start| visit(start)).collect();
+    while let Some(state) = stack.pop() {
+        for next in neighbours(&state) {
+            if visit(&next) {
+                stack.push(next);
+            }
+        }
+    }
+}
+
+/// All states reachable from the start states, including the start states.
+pub fn reachable<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: impl FnMut(&S) -> I,
+) -> HashSet<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = HashSet::new();
+    traverse(starts, neighbours, |state| visited.insert(state.clone()));
+    visited
+}
+
+/// Cheapest paths from the start states, where `neighbours` gives the
+/// states reachable in one step together with the cost of that step.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new();
+    // The heap holds indices into `states`, so that states need not be ordered
+    let mut states: Vec<S> = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if !search.contains(&start) {
+            search.costs.insert(start.clone(), C::default());
+            heap.push(Reverse((C::default(), states.len())));
+            states.push(start);
+        }
+    }
+
+    while let Some(Reverse((cost, inx))) = heap.pop() {
+        let state = states[inx].clone();
+        if search.costs[&state] < cost {
+            // Already reached more cheaply
+            continue;
+        }
+        for (next, step_cost) in neighbours(&state) {
+            let next_cost = cost + step_cost;
+            if search.cost(&next).is_some_and(|known| known <= next_cost) {
+                continue;
+            }
+            search.costs.insert(next.clone(), next_cost);
+            search.parents.insert(next.clone(), state.clone());
+            heap.push(Reverse((next_cost, states.len())));
+            states.push(next);
+        }
+    }
+    search
+}
+
+/// Cheapest path from `start` to a state for which `is_goal` holds, guided
+/// by a `heuristic` that must never overestimate the remaining cost.
+///
+/// Returns the cost and the states on the path, including start and goal.
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    heuristic: impl Fn(&S) -> C,
+    is_goal: impl Fn(&S) -> bool,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new();
+    let mut states = vec![start.clone()];
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&start), C::default(), 0)));
+    search.costs.insert(start, C::default());
+
+    while let Some(Reverse((_, cost, inx))) = heap.pop() {
+        let state = states[inx].clone();
+        if search.costs[&state] < cost {
+            continue;
+        }
+        if is_goal(&state) {
+            return Some((cost, search.path_to(&state).unwrap()));
+        }
+        for (next, step_cost) in neighbours(&state) {
+            let next_cost = cost + step_cost;
+            if search.cost(&next).is_some_and(|known| known <= next_cost) {
+                continue;
+            }
+            search.costs.insert(next.clone(), next_cost);
+            search.parents.insert(next.clone(), state.clone());
+            heap.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                states.len(),
+            )));
+            states.push(next);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    // Walls are '#', and entering a digit costs that digit
+    const MAZE: &str = "\
+.....
+.###.
+.#9..
+.#.#.
+...#.";
+
+    fn steps(grid: &Grid<char>, pos: (usize, usize)) -> Vec<((usize, usize), u32)> {
+        grid.neighbours4(pos)
+            .filter(|next| grid[*next] != '#')
+            .map(|next| (next, grid[next].to_digit(10).unwrap_or(1)))
+            .collect()
+    }
+
+    #[test]
+    fn bfs_distances_and_path() {
+        let grid = Grid::parse(0, MAZE, Some).unwrap();
+        let search = bfs([(0, 0)], |pos| {
+            steps(&grid, *pos).into_iter().map(|(next, _)| next)
+        });
+        assert_eq!(search.cost(&(4, 2)), Some(6));
+        assert_eq!(search.cost(&(2, 2)), Some(8));
+        assert_eq!(search.cost(&(1, 1)), None);
+        assert_eq!(search.path_to(&(0, 2)), Some(vec![(0, 0), (0, 1), (0, 2)]));
+    }
+
+    #[test]
+    fn reachable_states() {
+        let grid = Grid::parse(0, MAZE, Some).unwrap();
+        let reached = reachable([(0, 0)], |pos| {
+            steps(&grid, *pos).into_iter().map(|(next, _)| next)
+        });
+        assert_eq!(reached.len(), 18);
+        assert!(reached.contains(&(2, 2)));
+        assert!(!reached.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn weighted_searches() {
+        let grid = Grid::parse(0, MAZE, Some).unwrap();
+        let search = dijkstra([(0, 0)], |pos| steps(&grid, *pos));
+        assert_eq!(search.cost(&(2, 2)), Some(16));
+        assert_eq!(search.cost(&(2, 3)), Some(7));
+
+        let goal = (4, 2);
+        let manhattan =
+            |pos: &(usize, usize)| (pos.0.abs_diff(goal.0) + pos.1.abs_diff(goal.1)) as u32;
+        let (cost, path) = astar(
+            (0, 0),
+            |pos| steps(&grid, *pos),
+            manhattan,
+            |pos| *pos == goal,
+        )
+        .unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert_eq!(search.cost(&goal), Some(cost));
+    }
+}
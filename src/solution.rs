@@ -0,0 +1,84 @@
+use std::fmt;
+
+use crate::{
+    answer::Answer,
+    bench::{self, Timings},
+    image,
+    parsing::ParseError,
+};
+
+/// One of the two parts of a day's puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A solution to the puzzle of a single day.
+///
+/// The puzzle input is parsed once, and the parsed input is then handed to
+/// each of the two parts.
+pub trait Solution {
+    type Input;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+}
+
+/// Object safe wrapper around `Solution`, so that the solutions of all days
+/// can be kept together in the registry and run on several threads.
+pub trait Runnable: Sync {
+    fn run(&self, contents: &str, part: Part) -> Result<Answer, ParseError>;
+
+    /// Time parsing and each of the given parts separately, running each
+    /// of them `iterations` times.
+    fn bench(
+        &self,
+        contents: &str,
+        parts: &[Part],
+        iterations: usize,
+    ) -> Result<Timings, ParseError>;
+}
+
+impl<S: Solution + Sync> Runnable for S {
+    fn run(&self, contents: &str, part: Part) -> Result<Answer, ParseError> {
+        let input = S::parse(contents)?;
+        Ok(match part {
+            Part::One => S::part1(&input),
+            Part::Two => S::part2(&input),
+        })
+    }
+
+    fn bench(
+        &self,
+        contents: &str,
+        parts: &[Part],
+        iterations: usize,
+    ) -> Result<Timings, ParseError> {
+        let input = S::parse(contents)?;
+        // Saving images would distort the timings
+        image::without_images(|| {
+            let mut timings = Timings {
+                parse: bench::time(iterations, || S::parse(contents)),
+                ..Timings::default()
+            };
+            if parts.contains(&Part::One) {
+                timings.part1 = Some(bench::time(iterations, || S::part1(&input)));
+            }
+            if parts.contains(&Part::Two) {
+                timings.part2 = Some(bench::time(iterations, || S::part2(&input)));
+            }
+            Ok(timings)
+        })
+    }
+}
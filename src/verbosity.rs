@@ -0,0 +1,22 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+
+/// Set how much diagnostic output the solutions print. 0 prints nothing.
+pub fn set_verbosity(level: u8) {
+    VERBOSITY.store(level, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> u8 {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// Print a diagnostic message to stderr if the verbosity is at least `level`.
+#[macro_export]
+macro_rules! verbose {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::verbosity::verbosity() >= $level {
+            eprintln!($($arg)*);
+        }
+    };
+}
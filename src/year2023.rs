@@ -4,6 +4,7 @@ pub mod day11;
 pub mod day12;
 pub mod day13;
 pub mod day14;
+pub mod day15;
 pub mod day16;
 pub mod day2;
 pub mod day3;
@@ -13,3 +14,24 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+
+use crate::registry::Registry;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(2023, 1, &day1::Day1);
+    registry.register(2023, 2, &day2::Day2);
+    registry.register(2023, 3, &day3::Day3);
+    registry.register(2023, 4, &day4::Day4);
+    registry.register(2023, 5, &day5::Day5);
+    registry.register(2023, 6, &day6::Day6);
+    registry.register(2023, 7, &day7::Day7);
+    registry.register(2023, 8, &day8::Day8);
+    registry.register(2023, 9, &day9::Day9);
+    registry.register(2023, 10, &day10::Day10);
+    registry.register(2023, 11, &day11::Day11);
+    registry.register(2023, 12, &day12::Day12);
+    registry.register(2023, 13, &day13::Day13);
+    registry.register(2023, 14, &day14::Day14);
+    registry.register(2023, 15, &day15::Day15);
+    registry.register(2023, 16, &day16::Day16);
+}
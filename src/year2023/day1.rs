@@ -1,78 +1,105 @@
-use std::{fs, path::Path};
+use crate::{
+    answer::Answer,
+    parsing::{self, ParseError},
+    solution::Solution,
+    verbose,
+};
 
-pub fn problem1() {
-    println!("problem 1");
+const DAY: u32 = 1;
 
-    let path = Path::new("resources/day1_input");
-    let contents = fs::read_to_string(path).unwrap();
-    let mut sum = 0;
+const TEXT_NUMBERS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
 
-    contents.lines().into_iter().for_each(|line| {
-        if let Some(first) = line.find(char::is_numeric) {
-            if let Some(last) = line.rfind(char::is_numeric) {
-                sum += line.chars().nth(first).unwrap().to_digit(10).unwrap() * 10
-                    + line.chars().nth(last).unwrap().to_digit(10).unwrap();
-            }
-        }
-    });
+pub struct Day1;
 
-    println!("Answer: {}", sum);
-}
+impl Solution for Day1 {
+    type Input = Vec<String>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parsing::lines(DAY, contents)
+            .map(|line| {
+                let text = line.text();
+                line.map_chars(text, |c| c.is_ascii_alphanumeric().then_some(c))?;
+                // Every line needs a first and last digit, spelled out or not
+                let has_digit = text.contains(|c: char| c.is_ascii_digit())
+                    || TEXT_NUMBERS.iter().any(|text_num| text.contains(text_num));
+                if !has_digit {
+                    return Err(line.error(text, "expected a digit"));
+                }
+                Ok(text.to_string())
+            })
+            .collect()
+    }
 
-pub fn problem2() {
-    println!("problem 2");
+    fn part1(input: &Self::Input) -> Answer {
+        let mut sum = 0;
 
-    let path = Path::new("resources/day1_input");
-    let contents = fs::read_to_string(path).unwrap();
+        input.iter().for_each(|line| {
+            if let Some(first) = line.find(char::is_numeric) {
+                if let Some(last) = line.rfind(char::is_numeric) {
+                    sum += line.chars().nth(first).unwrap().to_digit(10).unwrap() * 10
+                        + line.chars().nth(last).unwrap().to_digit(10).unwrap();
+                }
+            }
+        });
 
-    let text_numbers = vec![
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    ];
-    let mut sum = 0;
+        sum.into()
+    }
 
-    contents.lines().into_iter().for_each(|line| {
-        let mut first = None;
-        if let Some(line_inx) = line.find(char::is_numeric) {
-            first = Some((
-                line_inx,
-                line.chars().nth(line_inx).unwrap().to_digit(10).unwrap(),
-            ));
-        }
-        text_numbers
-            .iter()
-            .enumerate()
-            .for_each(|(vec_inx, text_num)| {
-                if let Some(line_inx) = line.find(*text_num) {
-                    if first.is_none() || line_inx < first.unwrap().0 {
-                        first = Some((line_inx, (vec_inx + 1) as u32));
+    fn part2(input: &Self::Input) -> Answer {
+        let mut sum = 0;
+
+        input.iter().for_each(|line| {
+            let mut first = None;
+            if let Some(line_inx) = line.find(char::is_numeric) {
+                first = Some((
+                    line_inx,
+                    line.chars().nth(line_inx).unwrap().to_digit(10).unwrap(),
+                ));
+            }
+            TEXT_NUMBERS
+                .iter()
+                .enumerate()
+                .for_each(|(vec_inx, text_num)| {
+                    if let Some(line_inx) = line.find(*text_num) {
+                        if first.is_none() || line_inx < first.unwrap().0 {
+                            first = Some((line_inx, (vec_inx + 1) as u32));
+                        }
                     }
-                }
-            });
-        let first_num = first.unwrap().1;
+                });
+            let first_num = first.unwrap().1;
 
-        let mut last = None;
-        if let Some(line_inx) = line.rfind(char::is_numeric) {
-            last = Some((
-                line_inx,
-                line.chars().nth(line_inx).unwrap().to_digit(10).unwrap(),
-            ));
-        }
-        text_numbers
-            .iter()
-            .enumerate()
-            .for_each(|(vec_inx, text_num)| {
-                if let Some(line_inx) = line.rfind(*text_num) {
-                    if last.is_none() || line_inx > last.unwrap().0 {
-                        last = Some((line_inx, (vec_inx + 1) as u32));
+            let mut last = None;
+            if let Some(line_inx) = line.rfind(char::is_numeric) {
+                last = Some((
+                    line_inx,
+                    line.chars().nth(line_inx).unwrap().to_digit(10).unwrap(),
+                ));
+            }
+            TEXT_NUMBERS
+                .iter()
+                .enumerate()
+                .for_each(|(vec_inx, text_num)| {
+                    if let Some(line_inx) = line.rfind(*text_num) {
+                        if last.is_none() || line_inx > last.unwrap().0 {
+                            last = Some((line_inx, (vec_inx + 1) as u32));
+                        }
                     }
-                }
-            });
-        let last_num = last.unwrap().1;
+                });
+            let last_num = last.unwrap().1;
 
-        //println!("line: {}, first: {}, last: {}", line, first_num, last_num);
+            verbose!(
+                2,
+                "line: {}, first: {}, last: {}",
+                line,
+                first_num,
+                last_num
+            );
 
-        sum += first_num * 10 + last_num;
-    });
+            sum += first_num * 10 + last_num;
+        });
 
-    println!("Answer: {}", sum);
+        sum.into()
+    }
 }
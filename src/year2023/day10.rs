@@ -1,12 +1,17 @@
-use std::{fs, path::Path};
-
-#[derive(Copy, Clone, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use crate::{
+    answer::Answer,
+    direction::{Direction, Pos},
+    grid::Grid,
+    image::{self, Exporter},
+    parsing::{self, ParseError},
+    render::{self, Renderer},
+    search,
+    solution::Solution,
+    verbose,
+    verbosity::verbosity,
+};
+
+const DAY: u32 = 10;
 
 fn new_direction(c: char, dir: Direction) -> Option<Direction> {
     match c {
@@ -36,114 +41,58 @@ fn new_direction(c: char, dir: Direction) -> Option<Direction> {
     }
 }
 
-fn new_position(
-    pos: (usize, usize),
-    dir: Direction,
-    map: &Vec<Vec<char>>,
-) -> Option<(usize, usize)> {
-    if map.is_empty() {
-        return None;
-    }
-    let mut new_pos = pos;
-    match dir {
-        Direction::Up => {
-            if new_pos.0 > 0 {
-                new_pos.0 -= 1;
-            } else {
-                return None;
-            }
-        }
-        Direction::Down => {
-            if new_pos.0 < map.len() - 1 {
-                new_pos.0 += 1;
-            } else {
-                return None;
-            }
-        }
-        Direction::Left => {
-            if new_pos.1 > 0 {
-                new_pos.1 -= 1;
-            } else {
-                return None;
-            }
-        }
-        Direction::Right => {
-            if new_pos.1 < map[0].len() - 1 {
-                new_pos.1 += 1;
-            } else {
-                return None;
-            }
-        }
+/// Directions a pipe connects to.
+fn connections(c: char) -> &'static [Direction] {
+    match c {
+        '|' => &[Direction::Up, Direction::Down],
+        '-' => &[Direction::Left, Direction::Right],
+        'L' => &[Direction::Up, Direction::Right],
+        'J' => &[Direction::Up, Direction::Left],
+        '7' => &[Direction::Down, Direction::Left],
+        'F' => &[Direction::Down, Direction::Right],
+        _ => &[],
     }
-    Some(new_pos)
 }
 
-pub fn problem1() {
-    println!("problem 1");
-
-    let path = Path::new("resources/day10_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    // Parse input
-    let mut map: Vec<Vec<char>> = Vec::new();
-    contents.lines().for_each(|line| {
-        map.push(line.chars().collect());
-    });
-
-    // Find starting position
-    let mut start_pos = None;
-    map.iter().enumerate().for_each(|(i, row)| {
-        row.iter().enumerate().for_each(|(j, c)| {
-            if *c == 'S' {
-                start_pos = Some((i, j));
-            }
-        })
-    });
-
-    // Check each of the four possible starting directions
-    let mut result = None;
-    for start_dir in [
-        Direction::Up,
-        Direction::Down,
-        Direction::Left,
-        Direction::Right,
-    ]
-    .into_iter()
-    {
-        println!("Checking starting direction {:?}", start_dir);
-
-        let mut pos = start_pos.unwrap();
+/// The two directions in which the start tile at `start_pos` connects to the
+/// loop, found by following the pipes until they lead back to the start.
+fn start_connections(map: &Grid<char>, start_pos: Pos) -> Option<[Direction; 2]> {
+    Direction::ALL.into_iter().find_map(|start_dir| {
         let mut dir = start_dir;
-        if let Some(p) = new_position(pos, dir, &map) {
-            pos = p;
-        } else {
-            continue;
-        }
-        let mut steps = 1; // already taken 1 step
-
-        loop {
-            let current_char = map[pos.0][pos.1];
-
-            if current_char == 'S' {
-                break;
-            }
-
-            // Update direction and position
-            if let Some(d) = new_direction(current_char, dir) {
-                dir = d;
-                pos = new_position(pos, dir, &map).unwrap();
-                steps += 1;
-            } else {
-                break;
-            }
+        let mut pos = start_pos.step_within(dir, map.width(), map.height())?;
+        while map[pos] != 'S' {
+            dir = new_direction(map[pos], dir)?;
+            pos = pos.step_within(dir, map.width(), map.height())?;
         }
+        Some([start_dir, dir.reverse()])
+    })
+}
 
-        if steps > 1 {
-            result = Some(steps / 2);
-        }
+/// Directions the tile at `pos` connects to, where the start tile connects
+/// in `start_dirs`.
+fn tile_connections<'a>(
+    map: &Grid<char>,
+    pos: Pos,
+    start_dirs: &'a [Direction],
+) -> &'a [Direction] {
+    match map[pos] {
+        'S' => start_dirs,
+        c => connections(c),
     }
+}
 
-    println!("Answer: {}", result.unwrap());
+/// Neighbouring tiles that are connected to the tile at `pos` by pipes from
+/// both sides.
+fn connected_tiles(map: &Grid<char>, pos: Pos, start_dirs: &[Direction]) -> Vec<Pos> {
+    tile_connections(map, pos, start_dirs)
+        .iter()
+        .filter_map(|dir| {
+            let next = pos.step_within(*dir, map.width(), map.height())?;
+            tile_connections(map, next, start_dirs)
+                .contains(&dir.reverse())
+                .then_some(next)
+        })
+        .collect()
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -154,199 +103,249 @@ enum Color {
     None,
 }
 
-fn set_color(map: &mut Vec<Vec<Color>>, i: usize, j: usize, color: Color) {
-    if i < map.len() && j < map[i].len() && map[i][j] != Color::Path {
-        map[i][j] = color;
+fn set_color(map: &mut Grid<Color>, pos: Option<Pos>, color: Color) {
+    if let Some(c) = pos.and_then(|pos| map.get_mut(pos.into())) {
+        if *c != Color::Path {
+            *c = color;
+        }
     }
 }
 
-fn get_left_right(map: &Vec<Vec<Color>>, i: usize, j: usize) -> Option<Color> {
-    if i < map.len() - 1 && (map[i + 1][j] == Color::Left || map[i + 1][j] == Color::Right) {
-        return Some(map[i + 1][j]);
-    }
-    if j < map.len() - 1 && (map[i][j + 1] == Color::Left || map[i][j + 1] == Color::Right) {
-        return Some(map[i][j + 1]);
-    }
-    if i > 0 && (map[i - 1][j] == Color::Left || map[i - 1][j] == Color::Right) {
-        return Some(map[i - 1][j]);
-    }
-    if j > 0 && (map[i][j - 1] == Color::Left || map[i][j - 1] == Color::Right) {
-        return Some(map[i][j - 1]);
-    }
-    None
+fn get_left_right(map: &Grid<Color>, pos: (usize, usize)) -> Option<Color> {
+    map.neighbours4(pos)
+        .map(|p| map[p])
+        .find(|c| *c == Color::Left || *c == Color::Right)
 }
 
-fn update_color_map(color_map: &mut Vec<Vec<Color>>, dir: Direction, pos: (usize, usize)) {
-    match dir {
-        Direction::Up => {
-            if pos.1 > 0 {
-                set_color(color_map, pos.0, pos.1 - 1, Color::Left);
-            }
-            set_color(color_map, pos.0, pos.1 + 1, Color::Right);
-        }
-        Direction::Down => {
-            if pos.1 > 0 {
-                set_color(color_map, pos.0, pos.1 - 1, Color::Right);
-            }
-            set_color(color_map, pos.0, pos.1 + 1, Color::Left);
-        }
-        Direction::Left => {
-            if pos.0 > 0 {
-                set_color(color_map, pos.0 - 1, pos.1, Color::Right);
-            }
-            set_color(color_map, pos.0 + 1, pos.1, Color::Left);
+/// Color the tiles to the left and right of `pos` when moving in direction `dir`.
+fn update_color_map(color_map: &mut Grid<Color>, dir: Direction, pos: Pos) {
+    let (width, height) = (color_map.width(), color_map.height());
+    set_color(
+        color_map,
+        pos.step_within(dir.turn_left(), width, height),
+        Color::Left,
+    );
+    set_color(
+        color_map,
+        pos.step_within(dir.turn_right(), width, height),
+        Color::Right,
+    );
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Grid<char>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let map = Grid::parse(DAY, contents, |c| "|-LJ7F.S".contains(c).then_some(c))?;
+
+        // There must be a single start tile, which is part of a loop
+        let mut starts = parsing::lines(DAY, contents).flat_map(|line| {
+            line.text()
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == 'S')
+                .map(move |(col_inx, _)| (line, col_inx))
+        });
+        let Some((line, col_inx)) = starts.next() else {
+            let line_count = contents.lines().count().max(1);
+            return Err(ParseError::new(
+                DAY,
+                line_count,
+                1,
+                "",
+                "missing start tile",
+            ));
+        };
+        if let Some((line, col_inx)) = starts.next() {
+            return Err(line.error_at(col_inx, "S", "more than one start tile"));
         }
-        Direction::Right => {
-            if pos.0 > 0 {
-                set_color(color_map, pos.0 - 1, pos.1, Color::Left);
-            }
-            set_color(color_map, pos.0 + 1, pos.1, Color::Right);
+        let start_pos = map.find(|c| *c == 'S').map(Pos::from).unwrap();
+        if start_connections(&map, start_pos).is_none() {
+            return Err(line.error_at(col_inx, "S", "start tile is not part of a loop"));
         }
+
+        Ok(map)
     }
-}
 
-pub fn problem2() {
-    println!("problem 2");
+    fn part1(input: &Self::Input) -> Answer {
+        let map = input;
 
-    let path = Path::new("resources/day10_input");
-    let contents = fs::read_to_string(path).unwrap();
+        // Find starting position
+        let start_pos = map.find(|c| *c == 'S').map(Pos::from).unwrap();
+        let start_dirs = start_connections(map, start_pos).unwrap();
 
-    // Parse input
-    let mut map: Vec<Vec<char>> = Vec::new();
-    contents.lines().for_each(|line| {
-        map.push(line.chars().collect());
-    });
+        // Walk along the loop in both directions at once, the farthest tile
+        // is the last one reached
+        let search = search::bfs([start_pos], |pos| connected_tiles(map, *pos, &start_dirs));
+        verbose!(1, "Loop consists of {} tiles", search.visited().count());
 
-    // Find starting position
-    let mut start_pos = None;
-    map.iter().enumerate().for_each(|(i, row)| {
-        row.iter().enumerate().for_each(|(j, c)| {
-            if *c == 'S' {
-                start_pos = Some((i, j));
-            }
-        })
-    });
-
-    // Create coloring map
-    let mut color_map: Vec<Vec<Color>> = Vec::new();
-
-    // Check each of the four possible starting directions
-    let mut done = false;
-    for start_dir in [
-        Direction::Up,
-        Direction::Down,
-        Direction::Left,
-        Direction::Right,
-    ]
-    .into_iter()
-    {
-        // Reset color map
-        color_map = Vec::new();
-        map.iter().for_each(|v| {
-            color_map.push(vec![Color::None; v.len()]);
-        });
+        search
+            .visited()
+            .map(|(_, steps)| steps)
+            .max()
+            .unwrap()
+            .into()
+    }
 
-        let mut pos = start_pos.unwrap();
-        let mut dir = start_dir;
-        let mut prev_pos = pos;
-        if let Some(p) = new_position(pos, dir, &map) {
-            prev_pos = pos;
-            pos = p;
-        } else {
-            continue;
-        }
+    fn part2(input: &Self::Input) -> Answer {
+        let map = input;
 
-        loop {
-            let current_char = map[pos.0][pos.1];
+        // Find starting position
+        let start_pos = map.find(|c| *c == 'S').map(Pos::from);
+        let start_dirs = start_connections(map, start_pos.unwrap()).unwrap();
 
-            // Update color map
-            color_map[pos.0][pos.1] = Color::Path;
-            update_color_map(&mut color_map, dir, prev_pos);
-            update_color_map(&mut color_map, dir, pos);
+        // Create coloring map
+        let mut color_map = Grid::new(map.width(), map.height(), Color::None);
 
-            if current_char == 'S' {
-                done = true;
-                break;
-            }
+        // Walk the loop starting in one of the directions the start tile connects to
+        let mut done = false;
+        for start_dir in start_dirs {
+            // Reset color map
+            color_map = Grid::new(map.width(), map.height(), Color::None);
 
-            // Update direction and position
-            if let Some(d) = new_direction(current_char, dir) {
-                dir = d;
+            let mut pos = start_pos.unwrap();
+            let mut dir = start_dir;
+            let mut prev_pos;
+            if let Some(p) = pos.step_within(dir, map.width(), map.height()) {
                 prev_pos = pos;
-                pos = new_position(pos, dir, &map).unwrap();
+                pos = p;
             } else {
-                break;
+                continue;
+            }
+
+            loop {
+                let current_char = map[pos];
+
+                // Update color map
+                color_map[pos] = Color::Path;
+                update_color_map(&mut color_map, dir, prev_pos);
+                update_color_map(&mut color_map, dir, pos);
+
+                if current_char == 'S' {
+                    done = true;
+                    break;
+                }
+
+                // Update direction and position
+                if let Some(d) = new_direction(current_char, dir) {
+                    dir = d;
+                    prev_pos = pos;
+                    pos = pos.step_within(dir, map.width(), map.height()).unwrap();
+                } else {
+                    break;
+                }
             }
-        }
 
-        if done {
-            break;
+            if done {
+                break;
+            }
         }
-    }
 
-    // Color rest of map
-    let mut done = false;
-    while !done {
-        done = true;
-        let mut no_coloring = true;
-        for i in 0..color_map.len() {
-            for j in 0..color_map[i].len() {
-                if color_map[i][j] == Color::None {
-                    if let Some(color) = get_left_right(&color_map, i, j) {
-                        color_map[i][j] = color;
+        // Color rest of map
+        let mut done = false;
+        while !done {
+            done = true;
+            let mut no_coloring = true;
+            for pos in color_map.positions() {
+                if color_map[pos] == Color::None {
+                    if let Some(color) = get_left_right(&color_map, pos) {
+                        color_map[pos] = color;
                         no_coloring = false;
                     } else {
                         done = false;
                     }
                 }
             }
+            assert!(done || !no_coloring);
         }
-        assert!(!no_coloring);
-    }
 
-    // Find what is outside and what is inside
-    let outside = (|| {
-        for i in [0, color_map.len() - 1].into_iter() {
-            for j in [0, color_map[i].len() - 1].into_iter() {
-                let c = color_map[i][j];
-                if c == Color::Right || c == Color::Left {
-                    return Some(c);
-                }
-            }
+        // Find what is outside from the tiles on the border, if the loop
+        // does not cover all of them
+        let (height, width) = (color_map.height(), color_map.width());
+        let outside = color_map
+            .iter()
+            .filter(|((i, j), _)| *i == 0 || *j == 0 || *i == height - 1 || *j == width - 1)
+            .map(|(_, c)| *c)
+            .find(|c| *c != Color::Path);
+        let is_inside = |c: &Color| *c != Color::Path && Some(*c) != outside;
+
+        image::save_if_enabled("day10_inside", || {
+            Exporter::new(&color_map, |c| match c {
+                Color::Path => image::YELLOW,
+                c if is_inside(c) => image::GREEN,
+                _ => image::GRAY,
+            })
+            .to_image()
+        });
+
+        // Print map with the loop and the inside tiles highlighted
+        if verbosity() >= 2 {
+            let tiles_where = |predicate: &dyn Fn(&Color) -> bool| {
+                color_map
+                    .iter()
+                    .filter(|(_, c)| predicate(c))
+                    .map(|(pos, _)| pos)
+                    .collect::<Vec<_>>()
+            };
+            Renderer::new(map, |c| (*c, render::Color::Default))
+                .highlight(tiles_where(&|c| *c == Color::Path), render::Color::Yellow)
+                .highlight(tiles_where(&is_inside), render::Color::Green)
+                .print();
         }
-        None
-    })()
-    .unwrap();
 
-    let inside = match outside {
-        Color::Right => Some(Color::Left),
-        Color::Left => Some(Color::Right),
-        _ => None,
-    }
-    .unwrap();
-
-    // Print color map
-    // color_map.iter().for_each(|r| {
-    //     r.iter().for_each(|c| {
-    //         match *c {
-    //             Color::Path => print!("o"),
-    //             Color::Left => print!("L"),
-    //             Color::Right => print!("R"),
-    //             Color::None => print!("."),
-    //         };
-    //     });
-    //     println!("");
-    // });
-
-    // Count number of inside occurances
-    let mut sum = 0;
-    color_map.iter().for_each(|r| {
-        r.iter().for_each(|c| {
-            if *c == inside {
+        // Count number of inside occurances
+        let mut sum = 0;
+        color_map.iter().for_each(|(_, c)| {
+            if is_inside(c) {
                 sum += 1;
             }
-        })
-    });
+        });
 
-    println!("Answer: {}", sum);
+        sum.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
+
+    const EXAMPLE1: &str = include_str!("../../resources/day10_example1");
+    const EXAMPLE2: &str = include_str!("../../resources/day10_example2");
+
+    #[test]
+    fn part1_example1() {
+        assert_eq!(Day10.run(EXAMPLE1, Part::One), Ok(Answer::from(8)));
+    }
+
+    #[test]
+    fn pipes_off_the_loop() {
+        // The pipes left of the start touch it but are not part of the loop
+        let input = ".......\n-----S7\n.....LJ";
+        assert_eq!(Day10.run(input, Part::One), Ok(Answer::from(2)));
+        assert_eq!(Day10.run(input, Part::Two), Ok(Answer::from(0)));
+    }
+
+    #[test]
+    fn start_tile() {
+        assert_eq!(Day10::parse("").unwrap_err().message, "missing start tile");
+        assert_eq!(
+            Day10::parse("...\n.|.").unwrap_err().message,
+            "missing start tile"
+        );
+        let err = Day10::parse("S7.\nLS.").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.message, "more than one start tile");
+        assert_eq!(
+            Day10::parse(".S-\n.|.").unwrap_err().message,
+            "start tile is not part of a loop"
+        );
+    }
+
+    #[test]
+    fn part2_example2() {
+        assert_eq!(Day10.run(EXAMPLE2, Part::Two), Ok(Answer::from(4)));
+    }
 }
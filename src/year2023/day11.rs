@@ -1,74 +1,66 @@
-use std::{fs, path::Path};
+use crate::{
+    answer::Answer,
+    grid::Grid,
+    parsing::ParseError,
+    render::{Color, Renderer},
+    solution::Solution,
+    verbosity::verbosity,
+};
+
+const DAY: u32 = 11;
 
 fn distance(g1: (usize, usize), g2: (usize, usize)) -> usize {
     std::cmp::max(g1.0, g2.0) - std::cmp::min(g1.0, g2.0) + std::cmp::max(g1.1, g2.1)
         - std::cmp::min(g1.1, g2.1)
 }
 
-fn solve(expansion_factor: usize) -> usize {
-    let path = Path::new("resources/day11_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    // Parse input
-    let mut map: Vec<Vec<char>> = Vec::new();
-    contents.lines().for_each(|line| {
-        map.push(line.chars().collect());
-    });
+/// Sum of distances between all pairs of galaxies, when every empty row and
+/// column is replaced by `expansion_factor` empty rows or columns.
+pub fn solve(map: &Grid<char>, expansion_factor: usize) -> usize {
+    // Print map with the galaxies highlighted
+    if verbosity() >= 2 {
+        Renderer::new(map, |c| match c {
+            '#' => ('#', Color::Yellow),
+            _ => (*c, Color::Gray),
+        })
+        .print();
+    }
 
-    // Print map
-    // map.iter().for_each(|r| {
-    //     r.iter().for_each(|c| print!("{}", c));
-    //     println!("");
-    // });
+    let nrows = map.height();
+    let ncols = map.width();
 
     // Row expansion
-    let mut row_expansion = vec![0; map.len()];
+    let mut row_expansion = vec![0; nrows];
     let mut expansion = 0;
-    for i in 0..map.len() {
-        let mut do_expansion = true;
-        for j in 0..map.len() {
-            if map[i][j] != '.' {
-                do_expansion = false;
-                break;
-            }
-        }
-        if do_expansion {
+    for (i, row) in map.rows().enumerate() {
+        if row.iter().all(|c| *c == '.') {
             expansion += 1;
         }
         row_expansion[i] = expansion;
     }
 
     // Column expansion
-    let mut col_expansion = vec![0; map[0].len()];
+    let mut col_expansion = vec![0; ncols];
     let mut expansion = 0;
-    for j in 0..map.len() {
-        let mut do_expansion = true;
-        for i in 0..map.len() {
-            if map[i][j] != '.' {
-                do_expansion = false;
-                break;
-            }
-        }
-        if do_expansion {
+    for (j, col_exp) in col_expansion.iter_mut().enumerate() {
+        if map.column(j).all(|c| *c == '.') {
             expansion += 1;
         }
-        col_expansion[j] = expansion;
+        *col_exp = expansion;
     }
 
     // Find all galaxies
     let mut galaxies: Vec<(usize, usize)> = Vec::new();
-    for i in 0..map.len() {
-        for j in 0..map.len() {
-            if map[i][j] == '#' {
-                galaxies.push((i, j));
-            }
+    for (pos, c) in map.iter() {
+        if *c == '#' {
+            galaxies.push(pos);
         }
     }
 
     // Apply expansion
-    for i in 0..galaxies.len() {
-        galaxies[i].0 += (expansion_factor - 1) * row_expansion[galaxies[i].0];
-        galaxies[i].1 += (expansion_factor - 1) * col_expansion[galaxies[i].1];
+    for galaxy in galaxies.iter_mut() {
+        galaxy.0 += (expansion_factor - 1) * row_expansion[galaxy.0];
+        galaxy.1 += (expansion_factor - 1) * col_expansion[galaxy.1];
     }
 
     // Find distance between all pairs
@@ -82,14 +74,45 @@ fn solve(expansion_factor: usize) -> usize {
     sum
 }
 
-pub fn problem1() {
-    println!("problem 1");
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Grid<char>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let map = Grid::parse(DAY, contents, |c| ".#".contains(c).then_some(c))?;
+        if map.find(|c| *c == '#').is_none() {
+            let line_count = contents.lines().count().max(1);
+            return Err(ParseError::new(DAY, line_count, 1, "", "no galaxies"));
+        }
+        Ok(map)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve(input, 2).into()
+    }
 
-    println!("Answer={}", solve(2));
+    fn part2(input: &Self::Input) -> Answer {
+        solve(input, 1000000).into()
+    }
 }
 
-pub fn problem2() {
-    println!("problem 2");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
 
-    println!("Answer={}", solve(1000000));
+    const EXAMPLE: &str = include_str!("../../resources/day11_example");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day11.run(EXAMPLE, Part::One), Ok(Answer::from(374)));
+    }
+
+    #[test]
+    fn expansion_factors_example() {
+        let input = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(solve(&input, 10), 1030);
+        assert_eq!(solve(&input, 100), 8410);
+    }
 }
@@ -1,13 +1,37 @@
-use std::{fs, path::Path};
+use crate::{
+    answer::Answer,
+    parsing::{self, ParseError},
+    rng::Rng,
+    solution::Solution,
+    verbose,
+    verbosity::verbosity,
+};
+
+const DAY: u32 = 12;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Spring {
+pub enum Spring {
     Operational,
     Damaged,
     Unknown,
 }
 
-fn is_valid(list: &Vec<Spring>, groups: &Vec<usize>) -> bool {
+impl Spring {
+    fn to_char(self) -> char {
+        match self {
+            Spring::Operational => '.',
+            Spring::Damaged => '#',
+            Spring::Unknown => '?',
+        }
+    }
+}
+
+fn format_springs(list: &[Spring]) -> String {
+    list.iter().map(|spring| spring.to_char()).collect()
+}
+
+/// Whether `list`, without unknown springs, has exactly the damaged `groups`.
+pub fn is_valid(list: &[Spring], groups: &[usize]) -> bool {
     let damaged_count = list.iter().filter(|s| **s == Spring::Damaged).count();
     let expected_count = groups.iter().sum();
     if damaged_count != expected_count {
@@ -34,135 +58,341 @@ fn is_valid(list: &Vec<Spring>, groups: &Vec<usize>) -> bool {
         actual_groups.push(group_count);
     }
 
-    actual_groups == *groups
+    actual_groups == groups
 }
 
-fn solve(list: &Vec<Spring>, groups: &Vec<usize>) -> usize {
-    // Check count first
-    let mut damaged_count = 0;
-    let mut unknown_count = 0;
-    list.iter().for_each(|s| match s {
-        Spring::Damaged => damaged_count += 1,
-        Spring::Unknown => unknown_count += 1,
-        _ => (),
-    });
-    let expected_damaged_count = groups.iter().sum::<usize>();
-    if damaged_count > expected_damaged_count
-        || damaged_count + unknown_count < expected_damaged_count
-    {
-        return 0;
+/// A row of springs with the sizes of its groups of damaged springs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionRecord {
+    pub springs: Vec<Spring>,
+    pub groups: Vec<usize>,
+}
+
+impl ConditionRecord {
+    /// The record repeated `factor` times, with `separator` between the
+    /// copies of the springs.
+    pub fn unfold(&self, factor: usize, separator: Spring) -> ConditionRecord {
+        let mut springs = Vec::new();
+        for inx in 0..factor {
+            if inx > 0 {
+                springs.push(separator);
+            }
+            springs.extend(&self.springs);
+        }
+        ConditionRecord {
+            springs,
+            groups: self.groups.repeat(factor),
+        }
     }
 
-    // If there are no unknowns, then validate solution
-    if unknown_count == 0 {
-        if is_valid(&list, groups) {
-            return 1;
+    /// Number of arrangements of the springs that match the groups.
+    pub fn arrangements(&self) -> u128 {
+        ArrangementTable::new(self).total()
+    }
+
+    /// The springs that can be placed at the position of `state`, each with
+    /// the state that follows it.
+    fn next_states(&self, (inx, group_inx, run): State) -> [Option<(Spring, State)>; 2] {
+        let (spring, groups) = (self.springs[inx], &self.groups);
+        // An operational spring is only allowed between groups, and ends the
+        // current group if there is one
+        let operational = if spring == Spring::Damaged {
+            None
+        } else if run == 0 {
+            Some((Spring::Operational, (inx + 1, group_inx, 0)))
+        } else if run == groups[group_inx] {
+            Some((Spring::Operational, (inx + 1, group_inx + 1, 0)))
         } else {
-            return 0;
+            None
+        };
+        // A damaged spring extends the current group
+        let damaged =
+            (spring != Spring::Operational && group_inx < groups.len() && run < groups[group_inx])
+                .then_some((Spring::Damaged, (inx + 1, group_inx, run + 1)));
+        [operational, damaged]
+    }
+
+    /// Whether `state` at the end of the springs has all groups, possibly
+    /// with the last one ending at the last spring.
+    fn is_complete(&self, (_, group_inx, run): State) -> bool {
+        let group_count = self.groups.len();
+        (group_inx == group_count && run == 0)
+            || (group_inx + 1 == group_count && run == self.groups[group_inx])
+    }
+
+    /// All arrangements of the springs that match the groups, computed lazily.
+    pub fn arrangement_iter(&self) -> Arrangements<'_> {
+        ArrangementTable::new(self).into_iter()
+    }
+}
+
+/// State after placing the springs before `inx`: `(inx, group_inx, run)`,
+/// with `group_inx` groups completed and the last `run` springs damaged.
+type State = (usize, usize, usize);
+
+/// Number of arrangements of the remaining springs of a record for every
+/// state, computed with dynamic programming from the last spring to the
+/// first. Besides counting the arrangements, this lets them be enumerated and
+/// sampled without running into dead ends.
+pub struct ArrangementTable<'a> {
+    record: &'a ConditionRecord,
+    max_run: usize,
+    counts: Vec<u128>,
+}
+
+impl<'a> ArrangementTable<'a> {
+    pub fn new(record: &'a ConditionRecord) -> Self {
+        let (len, groups) = (record.springs.len(), &record.groups);
+        let max_run = groups.iter().copied().max().unwrap_or(0);
+        let mut table = ArrangementTable {
+            record,
+            max_run,
+            counts: vec![0; (len + 1) * (groups.len() + 1) * (max_run + 1)],
+        };
+        for inx in (0..=len).rev() {
+            for group_inx in 0..=groups.len() {
+                // Only runs that fit in the current group can be reached
+                let group_len = groups.get(group_inx).map_or(0, |len| *len);
+                for run in 0..=group_len {
+                    let state = (inx, group_inx, run);
+                    let count = if inx == len {
+                        record.is_complete(state) as u128
+                    } else {
+                        table.choices(state).map(|(_, _, count)| count).sum()
+                    };
+                    let table_inx = table.index(state);
+                    table.counts[table_inx] = count;
+                }
+            }
         }
+        table
+    }
+
+    fn index(&self, (inx, group_inx, run): State) -> usize {
+        (inx * (self.record.groups.len() + 1) + group_inx) * (self.max_run + 1) + run
+    }
+
+    /// Number of arrangements of the springs from the position of `state` on.
+    fn count(&self, state: State) -> u128 {
+        self.counts[self.index(state)]
+    }
+
+    /// Number of arrangements of the whole record.
+    pub fn total(&self) -> u128 {
+        self.count((0, 0, 0))
     }
 
-    // Recursively check possible solutions
-    let mut solutions = 0;
-    for inx in 0..list.len() {
-        if list[inx] == Spring::Unknown {
-            let mut list_clone = list.clone();
-            list_clone[inx] = Spring::Operational;
-            solutions += solve(&list_clone, &groups);
-            list_clone[inx] = Spring::Damaged;
-            solutions += solve(&list_clone, &groups);
-            break;
+    /// Springs that can be placed in `state` and lead to at least one
+    /// arrangement, with the next state and its count.
+    fn choices(&self, state: State) -> impl Iterator<Item = (Spring, State, u128)> + '_ {
+        self.record
+            .next_states(state)
+            .into_iter()
+            .flatten()
+            .map(|(spring, next)| (spring, next, self.count(next)))
+            .filter(|(_, _, count)| *count > 0)
+    }
+
+    /// A uniformly random arrangement, or `None` if there are none.
+    pub fn sample(&self, rng: &mut Rng) -> Option<Vec<Spring>> {
+        if self.total() == 0 {
+            return None;
+        }
+        let mut springs = Vec::new();
+        let mut state = (0, 0, 0);
+        while state.0 < self.record.springs.len() {
+            // Pick each spring in proportion to its number of arrangements
+            let mut pick = rng.below(self.count(state));
+            for (spring, next, count) in self.choices(state) {
+                if pick < count {
+                    springs.push(spring);
+                    state = next;
+                    break;
+                }
+                pick -= count;
+            }
         }
+        Some(springs)
     }
+}
+
+impl<'a> IntoIterator for ArrangementTable<'a> {
+    type Item = Vec<Spring>;
+    type IntoIter = Arrangements<'a>;
 
-    solutions
+    fn into_iter(self) -> Arrangements<'a> {
+        let stack = if self.total() > 0 {
+            vec![((0, 0, 0), Vec::new())]
+        } else {
+            Vec::new()
+        };
+        Arrangements { table: self, stack }
+    }
 }
 
-// fn solve2(list: &Vec<Spring>, groups: &Vec<usize>) -> usize {
-// {
-//     // ?#?#?#?#?#?#?#? 1,3,1,6
-//     //  | [-] | [----]
-
-//     // Setup groups in left most positions
-//     let mut group_start_inx: Vec<usize> = Vec::new();
-//     let mut group_inx = 0;
-//     for group_len in groups {
-//         group_start_inx.push(group_inx);
-//         group_inx += group_len + 1;
-//     }
-
-//     0
-// }
-
-pub fn problem1() {
-    println!("problem 1");
-
-    let path = Path::new("resources/day12_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut sum = 0;
-    contents.lines().for_each(|line| {
-        let (list_str, groups_str) = line.split_once(' ').unwrap();
-        let list = list_str
-            .chars()
-            .into_iter()
-            .map(|c| match c {
-                '.' => Spring::Operational,
-                '#' => Spring::Damaged,
-                '?' => Spring::Unknown,
-                _ => panic!("unknown character for spring"),
-            })
-            .collect::<Vec<Spring>>();
-        let groups = groups_str
-            .split(',')
-            .into_iter()
-            .map(|c| c.parse::<usize>().unwrap())
-            .collect::<Vec<usize>>();
+/// Iterator over the arrangements of a record, see `ConditionRecord::arrangement_iter`.
+pub struct Arrangements<'a> {
+    table: ArrangementTable<'a>,
+    /// Partial arrangements with their state, all of which can be completed.
+    stack: Vec<(State, Vec<Spring>)>,
+}
 
-        let solutions = solve(&list, &groups);
-        sum += solutions;
-    });
+impl Iterator for Arrangements<'_> {
+    type Item = Vec<Spring>;
 
-    println!("Answer: {}", sum);
+    fn next(&mut self) -> Option<Vec<Spring>> {
+        while let Some((state, springs)) = self.stack.pop() {
+            if state.0 == self.table.record.springs.len() {
+                return Some(springs);
+            }
+            // Reversed, so that operational springs come first
+            let choices: Vec<(Spring, State, u128)> = self.table.choices(state).collect();
+            for (spring, next, _) in choices.into_iter().rev() {
+                let mut next_springs = springs.clone();
+                next_springs.push(spring);
+                self.stack.push((next, next_springs));
+            }
+        }
+        None
+    }
 }
 
-pub fn problem2() {
-    println!("problem 2");
+/// Number of copies of each record when the records are unfolded.
+const UNFOLD_FACTOR: usize = 5;
 
-    let path = Path::new("resources/day12_example");
-    let contents = fs::read_to_string(path).unwrap();
+pub struct Day12;
 
-    let mut sum = 0;
-    contents.lines().for_each(|line| {
-        let (list_str, groups_str) = line.split_once(' ').unwrap();
-        let list = list_str
-            .chars()
-            .into_iter()
-            .map(|c| match c {
-                '.' => Spring::Operational,
-                '#' => Spring::Damaged,
-                '?' => Spring::Unknown,
-                _ => panic!("unknown character for spring"),
+impl Solution for Day12 {
+    type Input = Vec<ConditionRecord>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parsing::lines(DAY, contents)
+            .map(|line| {
+                let (list_str, groups_str): (&str, &str) = line.scan(line.text(), "{} {}")?;
+                let springs = line.map_chars(list_str, |c| match c {
+                    '.' => Some(Spring::Operational),
+                    '#' => Some(Spring::Damaged),
+                    '?' => Some(Spring::Unknown),
+                    _ => None,
+                })?;
+                let groups = groups_str
+                    .split(',')
+                    .map(|c| line.parse::<usize>(c))
+                    .collect::<Result<Vec<usize>, ParseError>>()?;
+                Ok(ConditionRecord { springs, groups })
             })
-            .collect::<Vec<Spring>>();
-        let groups = groups_str
-            .split(',')
-            .into_iter()
-            .map(|c| c.parse::<usize>().unwrap())
-            .collect::<Vec<usize>>();
-
-        let mut list_unfolded = list.clone();
-        let mut groups_unfolded = groups.clone();
-        for _ in 0..5 {
-            list_unfolded.push(Spring::Unknown);
-            list_unfolded.append(&mut list.clone());
-            groups_unfolded.append(&mut groups.clone());
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        // Print the first few arrangements of every record
+        if verbosity() >= 3 {
+            for record in input {
+                eprintln!("{} {:?}", format_springs(&record.springs), record.groups);
+                for springs in record.arrangement_iter().take(5) {
+                    eprintln!("  {}", format_springs(&springs));
+                }
+            }
         }
 
-        let solutions = solve(&list_unfolded, &groups_unfolded);
-        println!("solutions={}", solutions);
-        sum += solutions;
-    });
+        let sum: u128 = input.iter().map(|record| record.arrangements()).sum();
+
+        sum.into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let sum: u128 = input
+            .iter()
+            .map(|record| {
+                let solutions = record.unfold(UNFOLD_FACTOR, Spring::Unknown).arrangements();
+                verbose!(2, "solutions={}", solutions);
+                solutions
+            })
+            .sum();
 
-    println!("Answer: {}", sum);
+        sum.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
+
+    const EXAMPLE: &str = include_str!("../../resources/day12_example");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day12.run(EXAMPLE, Part::One), Ok(Answer::from(21)));
+    }
+
+    #[test]
+    fn arrangements_per_record() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        let counts: Vec<u128> = input.iter().map(|record| record.arrangements()).collect();
+        assert_eq!(counts, [1, 4, 1, 1, 4, 10]);
+    }
+
+    #[test]
+    fn unfolded_arrangements() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        let record = input[0].unfold(2, Spring::Unknown);
+        assert_eq!(record.springs.len(), 2 * 7 + 1);
+        assert_eq!(record.groups, [1, 1, 3, 1, 1, 3]);
+
+        let counts: Vec<u128> = input
+            .iter()
+            .map(|record| record.unfold(5, Spring::Unknown).arrangements())
+            .collect();
+        assert_eq!(counts, [1, 16384, 1, 16, 2500, 506250]);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day12.run(EXAMPLE, Part::Two), Ok(Answer::from(525152)));
+    }
+
+    #[test]
+    fn enumerate_arrangements() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        for record in &input {
+            let arrangements: Vec<Vec<Spring>> = record.arrangement_iter().collect();
+            assert_eq!(arrangements.len() as u128, record.arrangements());
+            for springs in &arrangements {
+                assert!(is_valid(springs, &record.groups));
+                let matches = springs
+                    .iter()
+                    .zip(&record.springs)
+                    .all(|(spring, known)| *known == Spring::Unknown || spring == known);
+                assert!(matches);
+            }
+        }
+
+        let first: Vec<String> = input[1]
+            .arrangement_iter()
+            .take(2)
+            .map(|springs| format_springs(&springs))
+            .collect();
+        assert_eq!(first, ["..#...#...###.", "..#..#....###."]);
+
+        let impossible = Day12::parse("#.# 1").unwrap();
+        assert_eq!(impossible[0].arrangement_iter().next(), None);
+    }
+
+    #[test]
+    fn sample_arrangements() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        let record = &input[5];
+        let table = ArrangementTable::new(record);
+        let mut rng = Rng::new(12);
+        let mut seen = std::collections::BTreeMap::new();
+        for _ in 0..1000 {
+            let springs = table.sample(&mut rng).unwrap();
+            assert!(is_valid(&springs, &record.groups));
+            *seen.entry(springs).or_insert(0) += 1;
+        }
+        // All 10 arrangements turn up about equally often
+        assert_eq!(seen.len(), 10);
+        assert!(seen.values().all(|count| (50..150).contains(count)));
+    }
 }
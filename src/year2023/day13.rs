@@ -1,29 +1,40 @@
-use std::{fs, path::Path};
+use crate::{
+    answer::Answer,
+    grid::Grid,
+    parsing::{self, ParseError},
+    render::{Color, Renderer},
+    solution::Solution,
+    verbosity::verbosity,
+};
+
+const DAY: u32 = 13;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Item {
+pub enum Item {
     Ash,
     Rock,
 }
 
+/// A pattern, stored both as it is and transposed, so that reflections
+/// between rows and between columns can be found the same way.
 #[derive(Debug, Clone)]
-struct Pattern {
-    rows: Vec<Vec<Item>>,
-    columns: Vec<Vec<Item>>,
+pub struct Pattern {
+    pub rows: Grid<Item>,
+    pub columns: Grid<Item>,
 }
 
 impl Pattern {
-    pub fn new() -> Self {
+    pub fn new(rows: Grid<Item>) -> Self {
         Pattern {
-            rows: Vec::new(),
-            columns: Vec::new(),
+            columns: rows.transpose(),
+            rows,
         }
     }
 }
 
-fn find_symmtery_one_dimension(items: &Vec<Vec<Item>>, avoid: Option<usize>) -> usize {
+fn find_symmtery_one_dimension(items: &Grid<Item>, avoid: Option<usize>) -> usize {
     let mut split_size = 0;
-    for split_inx in 0..items.len() - 1 {
+    for split_inx in 0..items.height() - 1 {
         if let Some(avoid_inx) = avoid {
             if avoid_inx == split_inx {
                 continue;
@@ -34,10 +45,10 @@ fn find_symmtery_one_dimension(items: &Vec<Vec<Item>>, avoid: Option<usize>) ->
         let mut left_inx = split_inx;
         let mut right_inx = split_inx + 1;
         loop {
-            if items[left_inx] != items[right_inx] {
+            if items.row(left_inx) != items.row(right_inx) {
                 break;
             }
-            if left_inx == 0 || right_inx == items.len() - 1 {
+            if left_inx == 0 || right_inx == items.height() - 1 {
                 split_size = std::cmp::max(split_size, split_inx + 1);
                 break;
             }
@@ -48,7 +59,8 @@ fn find_symmtery_one_dimension(items: &Vec<Vec<Item>>, avoid: Option<usize>) ->
     split_size
 }
 
-fn find_symmetry(pattern: &Pattern) -> usize {
+/// Summary value of the line of reflection of a pattern.
+pub fn find_symmetry(pattern: &Pattern) -> usize {
     let row_split_size = find_symmtery_one_dimension(&pattern.rows, None);
     if row_split_size > 0 {
         return 100 * row_split_size;
@@ -60,14 +72,15 @@ fn find_symmetry(pattern: &Pattern) -> usize {
     0
 }
 
-fn swapItem(item: Item) -> Item {
+fn swap_item(item: Item) -> Item {
     match item {
         Item::Ash => Item::Rock,
         Item::Rock => Item::Ash,
     }
 }
 
-fn find_smudged_symmetry(pattern: &Pattern) -> usize {
+/// Summary value of the line of reflection after fixing the one smudge.
+pub fn find_smudged_symmetry(pattern: &Pattern) -> usize {
     let original_row_split_size = find_symmtery_one_dimension(&pattern.rows, None);
     let original_col_split_size = find_symmtery_one_dimension(&pattern.columns, None);
 
@@ -82,13 +95,16 @@ fn find_smudged_symmetry(pattern: &Pattern) -> usize {
         None
     };
 
-    for i in 0..pattern.rows.len() {
-        for j in 0..pattern.columns.len() {
+    for i in 0..pattern.rows.height() {
+        for j in 0..pattern.rows.width() {
             let mut smudged_pattern: Pattern = pattern.clone();
-            smudged_pattern.rows[i][j] = swapItem(smudged_pattern.rows[i][j]);
-            smudged_pattern.columns[j][i] = swapItem(smudged_pattern.columns[j][i]);
+            smudged_pattern.rows[(i, j)] = swap_item(smudged_pattern.rows[(i, j)]);
+            smudged_pattern.columns[(j, i)] = swap_item(smudged_pattern.columns[(j, i)]);
 
-            assert_eq!(smudged_pattern.rows[i][j], smudged_pattern.columns[j][i]);
+            assert_eq!(
+                smudged_pattern.rows[(i, j)],
+                smudged_pattern.columns[(j, i)]
+            );
 
             let row_split_size = find_symmtery_one_dimension(&smudged_pattern.rows, row_avoid);
             if row_split_size > 0 {
@@ -103,76 +119,93 @@ fn find_smudged_symmetry(pattern: &Pattern) -> usize {
     0
 }
 
-fn read_input() -> Vec<Pattern> {
-    let path = Path::new("resources/day13_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut patterns: Vec<Pattern> = Vec::new();
-    let mut current_pattern: Option<Pattern> = None;
-
-    // Read file into pattern rows
-    contents.lines().for_each(|line| {
-        if line.is_empty() {
-            if let Some(pattern) = current_pattern.take() {
-                patterns.push(pattern);
-            }
-        } else {
-            if current_pattern.is_none() {
-                current_pattern = Some(Pattern::new());
-            }
-            if let Some(pattern) = &mut current_pattern {
-                pattern.rows.push(
-                    line.chars()
-                        .into_iter()
-                        .map(|c| match c {
-                            '.' => Item::Ash,
-                            '#' => Item::Rock,
-                            _ => panic!("Unknown item"),
-                        })
-                        .collect::<Vec<Item>>(),
-                );
-            }
-        }
-    });
+/// Print a pattern with the rows or columns on both sides of the line of
+/// reflection highlighted, given the summary value of the line.
+fn print_pattern(pattern: &Pattern, summary: usize) {
+    let rows = &pattern.rows;
+    let mirrored: Vec<(usize, usize)> = if summary >= 100 {
+        let split = summary / 100;
+        rows.positions()
+            .filter(|(i, _)| *i + 1 == split || *i == split)
+            .collect()
+    } else {
+        rows.positions()
+            .filter(|(_, j)| *j + 1 == summary || *j == summary)
+            .collect()
+    };
+    Renderer::new(rows, |item| match item {
+        Item::Ash => ('.', Color::Default),
+        Item::Rock => ('#', Color::Default),
+    })
+    .highlight(mirrored, Color::Blue)
+    .print();
+}
 
-    // Add last pattern row
-    if let Some(pattern) = current_pattern.take() {
-        patterns.push(pattern);
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Pattern>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        // Patterns are separated by empty lines
+        parsing::paragraphs(DAY, contents)
+            .into_iter()
+            .map(|lines| {
+                let rows = Grid::from_lines(lines, |c| match c {
+                    '.' => Some(Item::Ash),
+                    '#' => Some(Item::Rock),
+                    _ => None,
+                })?;
+                Ok(Pattern::new(rows))
+            })
+            .collect()
     }
 
-    // Make columns
-    patterns.iter_mut().for_each(|pattern| {
-        if pattern.rows.is_empty() {
-            return;
-        }
-        let nrows = pattern.rows.len();
-        let ncols = pattern.rows.first().unwrap().len();
-        for j in 0..ncols {
-            let mut column = Vec::new();
-            for i in 0..nrows {
-                column.push(pattern.rows[i][j]);
-            }
-            pattern.columns.push(column);
-        }
-    });
+    fn part1(input: &Self::Input) -> Answer {
+        let sum: usize = input
+            .iter()
+            .map(|pattern| {
+                let summary = find_symmetry(pattern);
+                if verbosity() >= 2 {
+                    print_pattern(pattern, summary);
+                }
+                summary
+            })
+            .sum();
+
+        sum.into()
+    }
 
-    patterns
+    fn part2(input: &Self::Input) -> Answer {
+        let sum: usize = input
+            .iter()
+            .map(|pattern| {
+                let summary = find_smudged_symmetry(pattern);
+                if verbosity() >= 2 {
+                    print_pattern(pattern, summary);
+                }
+                summary
+            })
+            .sum();
+
+        sum.into()
+    }
 }
 
-pub fn problem1() {
-    println!("problem 1");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
 
-    let patterns = read_input();
-    let sum: usize = patterns.iter().map(|p| find_symmetry(p)).sum();
+    const EXAMPLE: &str = include_str!("../../resources/day13_example");
 
-    println!("Answer: {}", sum);
-}
-
-pub fn problem2() {
-    println!("problem 2");
-
-    let patterns = read_input();
-    let sum: usize = patterns.iter().map(|p| find_smudged_symmetry(p)).sum();
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day13.run(EXAMPLE, Part::One), Ok(Answer::from(405)));
+    }
 
-    println!("Answer: {}", sum);
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day13.run(EXAMPLE, Part::Two), Ok(Answer::from(400)));
+    }
 }
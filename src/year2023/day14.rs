@@ -1,126 +1,63 @@
-use std::collections::hash_map::DefaultHasher;
-use std::collections::BTreeMap;
-use std::hash::{Hash, Hasher};
-use std::{fs, path::Path};
+use crate::{
+    answer::Answer,
+    cycle,
+    grid::Grid,
+    image::{self, Exporter},
+    parsing::ParseError,
+    render::{Color, Renderer},
+    solution::Solution,
+    verbose,
+    verbosity::verbosity,
+};
+
+const DAY: u32 = 14;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Item {
+pub enum Item {
     RoundRock,
     SquareRock,
     Nothing,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Platform {
-    map: Vec<Vec<Item>>,
+pub struct Platform {
+    pub map: Grid<Item>,
 }
 
-impl Platform {
-    pub fn new() -> Self {
-        Platform { map: Vec::new() }
-    }
-}
-
-fn read_input() -> Platform {
-    let path = Path::new("resources/day14_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut platform = Platform::new();
-
-    // Read file into pattern rows
-    contents.lines().for_each(|line| {
-        if line.is_empty() {
-            return;
-        }
-        platform.map.push(
-            line.chars()
-                .into_iter()
-                .map(|c| match c {
-                    '.' => Item::Nothing,
-                    '#' => Item::SquareRock,
-                    'O' => Item::RoundRock,
-                    _ => panic!("Unknown item"),
-                })
-                .collect::<Vec<Item>>(),
-        );
-    });
-
-    platform
+fn print_platform(platform: &Platform) {
+    Renderer::new(&platform.map, |item| match item {
+        Item::Nothing => ('.', Color::Default),
+        Item::RoundRock => ('O', Color::Yellow),
+        Item::SquareRock => ('#', Color::Gray),
+    })
+    .print();
 }
 
-pub fn problem1() {
-    println!("problem 1");
-
-    let mut platform = read_input();
-
-    // Tilt platform
-    let mut something_moved = true;
-    while something_moved {
-        something_moved = false;
-        for i in 1..platform.map.len() {
-            for j in 0..platform.map[i].len() {
-                if platform.map[i][j] == Item::RoundRock && platform.map[i - 1][j] == Item::Nothing
-                {
-                    // Move item up
-                    platform.map[i - 1][j] = Item::RoundRock;
-                    platform.map[i][j] = Item::Nothing;
-                    something_moved = true;
-                }
-            }
-        }
-    }
-
-    // Calculate load
-    let nrows = platform.map.len();
-    let load = platform
-        .map
-        .iter()
-        .enumerate()
-        .map(|(inx, row)| {
-            row.iter()
-                .map(|c| {
-                    if *c == Item::RoundRock {
-                        nrows - inx
-                    } else {
-                        0
-                    }
-                })
-                .sum::<usize>()
+fn save_platform_image(name: &str, platform: &Platform) {
+    image::save_if_enabled(name, || {
+        Exporter::new(&platform.map, |item| match item {
+            Item::Nothing => image::BLACK,
+            Item::RoundRock => image::YELLOW,
+            Item::SquareRock => image::GRAY,
         })
-        .sum::<usize>();
-
-    println!("Answer: {}", load);
-}
-
-fn print_platform(platform: &Platform) {
-    platform.map.iter().for_each(|r| {
-        r.iter().for_each(|item| {
-            print!(
-                "{}",
-                match item {
-                    Item::Nothing => '.',
-                    Item::RoundRock => 'O',
-                    Item::SquareRock => '#',
-                }
-            )
-        });
-        println!("");
+        .to_image()
     });
-    println!("");
 }
 
-fn do_cycle(platform: &mut Platform) {
+/// Tilt the platform north, west, south and east.
+pub fn do_cycle(platform: &mut Platform) {
     // Tilt platform North
     let mut something_moved = true;
     while something_moved {
         something_moved = false;
-        for i in 1..platform.map.len() {
-            for j in 0..platform.map[i].len() {
-                if platform.map[i][j] == Item::RoundRock && platform.map[i - 1][j] == Item::Nothing
+        for i in 1..platform.map.height() {
+            for j in 0..platform.map.width() {
+                if platform.map[(i, j)] == Item::RoundRock
+                    && platform.map[(i - 1, j)] == Item::Nothing
                 {
                     // Move item up
-                    platform.map[i - 1][j] = Item::RoundRock;
-                    platform.map[i][j] = Item::Nothing;
+                    platform.map[(i - 1, j)] = Item::RoundRock;
+                    platform.map[(i, j)] = Item::Nothing;
                     something_moved = true;
                 }
             }
@@ -131,13 +68,14 @@ fn do_cycle(platform: &mut Platform) {
     let mut something_moved = true;
     while something_moved {
         something_moved = false;
-        for j in 1..platform.map[0].len() {
-            for i in 0..platform.map.len() {
-                if platform.map[i][j] == Item::RoundRock && platform.map[i][j - 1] == Item::Nothing
+        for j in 1..platform.map.width() {
+            for i in 0..platform.map.height() {
+                if platform.map[(i, j)] == Item::RoundRock
+                    && platform.map[(i, j - 1)] == Item::Nothing
                 {
                     // Move item left
-                    platform.map[i][j - 1] = Item::RoundRock;
-                    platform.map[i][j] = Item::Nothing;
+                    platform.map[(i, j - 1)] = Item::RoundRock;
+                    platform.map[(i, j)] = Item::Nothing;
                     something_moved = true;
                 }
             }
@@ -148,13 +86,14 @@ fn do_cycle(platform: &mut Platform) {
     let mut something_moved = true;
     while something_moved {
         something_moved = false;
-        for i in (0..platform.map.len() - 1).rev() {
-            for j in 0..platform.map[i].len() {
-                if platform.map[i][j] == Item::RoundRock && platform.map[i + 1][j] == Item::Nothing
+        for i in (0..platform.map.height() - 1).rev() {
+            for j in 0..platform.map.width() {
+                if platform.map[(i, j)] == Item::RoundRock
+                    && platform.map[(i + 1, j)] == Item::Nothing
                 {
                     // Move item down
-                    platform.map[i + 1][j] = Item::RoundRock;
-                    platform.map[i][j] = Item::Nothing;
+                    platform.map[(i + 1, j)] = Item::RoundRock;
+                    platform.map[(i, j)] = Item::Nothing;
                     something_moved = true;
                 }
             }
@@ -165,13 +104,14 @@ fn do_cycle(platform: &mut Platform) {
     let mut something_moved = true;
     while something_moved {
         something_moved = false;
-        for j in (0..platform.map[0].len() - 1).rev() {
-            for i in 0..platform.map.len() {
-                if platform.map[i][j] == Item::RoundRock && platform.map[i][j + 1] == Item::Nothing
+        for j in (0..platform.map.width() - 1).rev() {
+            for i in 0..platform.map.height() {
+                if platform.map[(i, j)] == Item::RoundRock
+                    && platform.map[(i, j + 1)] == Item::Nothing
                 {
                     // Move item right
-                    platform.map[i][j + 1] = Item::RoundRock;
-                    platform.map[i][j] = Item::Nothing;
+                    platform.map[(i, j + 1)] = Item::RoundRock;
+                    platform.map[(i, j)] = Item::Nothing;
                     something_moved = true;
                 }
             }
@@ -179,65 +119,126 @@ fn do_cycle(platform: &mut Platform) {
     }
 }
 
-fn calculate_hash<T: Hash>(t: &T) -> u64 {
-    let mut s = DefaultHasher::new();
-    t.hash(&mut s);
-    s.finish()
-}
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Platform;
 
-pub fn problem2() {
-    println!("problem 2");
-
-    let mut platform = read_input();
-
-    let mut map: BTreeMap<u64, usize> = BTreeMap::new();
-    map.insert(calculate_hash(&platform), 0);
-
-    let iter_total = 1000000000;
-    let mut iter = 0;
-    for iter_inx in 0..iter_total {
-        do_cycle(&mut platform);
-        let hash = calculate_hash(&platform);
-        if let Some(inx) = map.get(&hash) {
-            println!(
-                "Found same platform state after {} cycles as after {} cycles!",
-                iter_inx, inx
-            );
-            let iter_left = iter_total - iter_inx - 1;
-            let iter_gap = iter_inx + 1 - inx;
-            let times = iter_left / iter_gap;
-            iter = (iter_inx + 1) + times * iter_gap;
-            break;
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let map = Grid::parse(DAY, contents, |c| match c {
+            '.' => Some(Item::Nothing),
+            '#' => Some(Item::SquareRock),
+            'O' => Some(Item::RoundRock),
+            _ => None,
+        })?;
+
+        Ok(Platform { map })
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let mut platform = input.clone();
+
+        // Tilt platform
+        let mut something_moved = true;
+        while something_moved {
+            something_moved = false;
+            for i in 1..platform.map.height() {
+                for j in 0..platform.map.width() {
+                    if platform.map[(i, j)] == Item::RoundRock
+                        && platform.map[(i - 1, j)] == Item::Nothing
+                    {
+                        // Move item up
+                        platform.map[(i - 1, j)] = Item::RoundRock;
+                        platform.map[(i, j)] = Item::Nothing;
+                        something_moved = true;
+                    }
+                }
+            }
         }
-        map.insert(hash, iter_inx + 1);
+        save_platform_image("day14_tilted", &platform);
+
+        // Calculate load
+        let nrows = platform.map.height();
+        let load = platform
+            .map
+            .rows()
+            .enumerate()
+            .map(|(inx, row)| {
+                row.iter()
+                    .map(|c| {
+                        if *c == Item::RoundRock {
+                            nrows - inx
+                        } else {
+                            0
+                        }
+                    })
+                    .sum::<usize>()
+            })
+            .sum::<usize>();
+
+        load.into()
     }
 
-    println!("Jumping to iteration number {}", iter);
-    for _ in iter..iter_total {
-        do_cycle(&mut platform);
+    fn part2(input: &Self::Input) -> Answer {
+        let iter_total = 1000000000;
+        let (cycle, platforms) = cycle::find(input.clone(), |platform| {
+            let mut platform = platform.clone();
+            do_cycle(&mut platform);
+            platform
+        });
+        verbose!(
+            1,
+            "Found same platform state after {} cycles as after {} cycles!",
+            cycle.start + cycle.length,
+            cycle.start
+        );
+
+        let iter = cycle.reduce(iter_total);
+        verbose!(1, "Using platform state after {} cycles", iter);
+        let platform = &platforms[iter];
+
+        if verbosity() >= 2 {
+            print_platform(platform);
+        }
+        save_platform_image("day14_cycled", platform);
+
+        // Calculate load
+        let nrows = platform.map.height();
+        let load = platform
+            .map
+            .rows()
+            .enumerate()
+            .map(|(inx, row)| {
+                row.iter()
+                    .map(|c| {
+                        if *c == Item::RoundRock {
+                            nrows - inx
+                        } else {
+                            0
+                        }
+                    })
+                    .sum::<usize>()
+            })
+            .sum::<usize>();
+
+        load.into()
     }
+}
 
-    // println!();
-    // print_platform(&platform);
-
-    // Calculate load
-    let nrows = platform.map.len();
-    let load = platform
-        .map
-        .iter()
-        .enumerate()
-        .map(|(inx, row)| {
-            row.iter()
-                .map(|c| {
-                    if *c == Item::RoundRock {
-                        nrows - inx
-                    } else {
-                        0
-                    }
-                })
-                .sum::<usize>()
-        })
-        .sum::<usize>();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
+
+    const EXAMPLE: &str = include_str!("../../resources/day14_example");
 
-    println!("Answer: {}", load);
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day14.run(EXAMPLE, Part::One), Ok(Answer::from(136)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day14.run(EXAMPLE, Part::Two), Ok(Answer::from(64)));
+    }
 }
@@ -0,0 +1,201 @@
+use crate::{
+    answer::Answer,
+    parsing::{self, Line, ParseError},
+    solution::Solution,
+    verbose,
+};
+
+const DAY: u32 = 15;
+
+/// The Holiday ASCII String Helper algorithm.
+pub fn hash(s: &str) -> u8 {
+    s.bytes().fold(0_u8, |value, byte| {
+        value.wrapping_add(byte).wrapping_mul(17)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Remove,      // label-
+    Insert(u32), // label=focal length
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub text: String,
+    pub label: String,
+    pub operation: Operation,
+}
+
+/// The boxes of lenses, indexed by the hash of the lens labels.
+#[derive(Debug, Clone)]
+pub struct LensLibrary {
+    /// Label and focal length of the lenses in each box, front to back.
+    boxes: Vec<Vec<(String, u32)>>,
+}
+
+impl LensLibrary {
+    pub fn new() -> Self {
+        LensLibrary {
+            boxes: vec![Vec::new(); 256],
+        }
+    }
+
+    /// Apply a step of the HASHMAP procedure.
+    pub fn apply(&mut self, step: &Step) {
+        let lenses = &mut self.boxes[hash(&step.label) as usize];
+        let lens_inx = lenses.iter().position(|(label, _)| *label == step.label);
+        match (&step.operation, lens_inx) {
+            (Operation::Remove, Some(inx)) => {
+                lenses.remove(inx);
+            }
+            (Operation::Remove, None) => (),
+            // Replace the lens with the same label, keeping its place
+            (Operation::Insert(focal_length), Some(inx)) => lenses[inx].1 = *focal_length,
+            (Operation::Insert(focal_length), None) => {
+                lenses.push((step.label.clone(), *focal_length));
+            }
+        }
+    }
+
+    pub fn focusing_power(&self) -> usize {
+        let mut power = 0;
+        for (box_inx, lenses) in self.boxes.iter().enumerate() {
+            for (slot_inx, (_, focal_length)) in lenses.iter().enumerate() {
+                power += (box_inx + 1) * (slot_inx + 1) * *focal_length as usize;
+            }
+        }
+        power
+    }
+}
+
+impl Default for LensLibrary {
+    fn default() -> Self {
+        LensLibrary::new()
+    }
+}
+
+/// Parse the `text` of a step that starts at character `col_inx` of `line`.
+fn parse_step(line: Line, col_inx: usize, text: String) -> Result<Step, ParseError> {
+    let error = |message: &str| line.error_at(col_inx, &text, message);
+    let (label, operation) = if let Some(label) = text.strip_suffix('-') {
+        (label, Operation::Remove)
+    } else if let Some((label, focal_length)) = text.split_once('=') {
+        match focal_length.as_bytes() {
+            [digit @ b'1'..=b'9'] => (label, Operation::Insert((digit - b'0') as u32)),
+            _ => return Err(error("expected a focal length from 1 to 9")),
+        }
+    } else {
+        return Err(error("expected '=' or '-'"));
+    };
+    if label.is_empty() || !label.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(error("expected a label of lowercase letters"));
+    }
+
+    Ok(Step {
+        label: label.to_string(),
+        text,
+        operation,
+    })
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<Step>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        // Newlines are ignored, so the steps may be split over several lines.
+        // Every step keeps the line and column where it starts.
+        let mut steps = Vec::new();
+        let mut step: Option<(Line, usize, String)> = None;
+        for line in parsing::lines(DAY, contents) {
+            for (col_inx, c) in line.text().chars().enumerate() {
+                match (c, &mut step) {
+                    (',', _) => steps.extend(step.take()),
+                    (c, Some((_, _, text))) => text.push(c),
+                    (c, None) => step = Some((line, col_inx, c.to_string())),
+                }
+            }
+        }
+        steps.extend(step);
+
+        steps
+            .into_iter()
+            .map(|(line, col_inx, text)| parse_step(line, col_inx, text))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let sum: u32 = input.iter().map(|step| hash(&step.text) as u32).sum();
+
+        sum.into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let mut library = LensLibrary::new();
+        for step in input {
+            library.apply(step);
+        }
+        verbose!(
+            2,
+            "boxes: {:?}",
+            library
+                .boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, lenses)| !lenses.is_empty())
+                .collect::<Vec<_>>()
+        );
+
+        library.focusing_power().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
+
+    const EXAMPLE: &str = include_str!("../../resources/day15_example");
+
+    #[test]
+    fn hash_algorithm() {
+        assert_eq!(hash("HASH"), 52);
+        assert_eq!(hash("rn"), 0);
+        assert_eq!(hash("qp"), 1);
+    }
+
+    #[test]
+    fn steps_split_over_lines() {
+        let steps = Day15::parse("rn=1,cm-,ot=\n7\n,ab=5\n").unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[2].text, "ot=7");
+        assert_eq!(steps[2].operation, Operation::Insert(7));
+    }
+
+    #[test]
+    fn invalid_steps() {
+        let error = |contents| {
+            let err = Day15::parse(contents).unwrap_err();
+            (err.line, err.column, err.message)
+        };
+        let label = "expected a label of lowercase letters".to_string();
+        let focal_length = "expected a focal length from 1 to 9".to_string();
+        assert_eq!(error("rn=1,=3"), (1, 6, label.clone()));
+        assert_eq!(error("rn=1,-"), (1, 6, label));
+        assert_eq!(error("rn=1,\ncm=0"), (2, 1, focal_length.clone()));
+        assert_eq!(error("rn=1,cm\n=10"), (1, 6, focal_length));
+        assert_eq!(error("rn=1,cm"), (1, 6, "expected '=' or '-'".to_string()));
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day15.run(EXAMPLE, Part::One), Ok(Answer::from(1320)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day15.run(EXAMPLE, Part::Two), Ok(Answer::from(145)));
+    }
+}
@@ -1,13 +1,19 @@
-use std::{
-    collections::{BTreeSet, LinkedList},
-    fs,
-    path::Path,
+use crate::{
+    answer::Answer,
+    direction::{Direction, Pos},
+    grid::Grid,
+    image::{self, Exporter},
+    parsing::ParseError,
+    render::{Color, Renderer},
+    search,
+    solution::Solution,
+    verbosity::verbosity,
 };
 
-use itertools::Itertools;
+const DAY: u32 = 16;
 
 #[derive(Debug)]
-enum Item {
+pub enum Item {
     Empty,              // Empty space .
     MirrorUpRight,      // Mirror /
     MirrorUpLeft,       // Mirror \
@@ -15,238 +21,188 @@ enum Item {
     SplitterHorizontal, // Splitter -
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
+/// A beam of light entering the tile at `pos` heading in direction `dir`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ray {
+    pub pos: Pos,
+    pub dir: Direction,
 }
 
-#[derive(Debug)]
-struct Ray {
-    row: usize,
-    col: usize,
-    dir: Direction,
+impl Ray {
+    /// Ray moved one step in direction `dir`, if it stays inside the map.
+    fn step(&self, map: &Grid<Item>, dir: Direction) -> Option<Ray> {
+        let pos = self.pos.step_within(dir, map.width(), map.height())?;
+        Some(Ray { pos, dir })
+    }
 }
 
-fn read_map() -> Vec<Vec<Item>> {
-    let path = Path::new("resources/day16_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    // Contruct map
-    let map = contents
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '.' => Item::Empty,
-                    '/' => Item::MirrorUpRight,
-                    '\\' => Item::MirrorUpLeft,
-                    '-' => Item::SplitterHorizontal,
-                    '|' => Item::SplitterVertical,
-                    _ => panic!("unexpected character: {}", c),
-                })
-                .collect_vec()
-        })
-        .collect_vec();
-
-    map
+/// The rays that continue from `ray` after passing through its tile.
+fn next_rays<'a>(map: &'a Grid<Item>, ray: &Ray) -> impl Iterator<Item = Ray> + 'a {
+    let (first, second) = match map[ray.pos] {
+        Item::Empty => (ray.dir, None),
+        Item::MirrorUpRight => (ray.dir.reflect_slash(), None),
+        Item::MirrorUpLeft => (ray.dir.reflect_backslash(), None),
+        Item::SplitterVertical | Item::SplitterHorizontal => {
+            let vertical = matches!(map[ray.pos], Item::SplitterVertical);
+            if ray.dir.is_vertical() != vertical {
+                (ray.dir.turn_left(), Some(ray.dir.turn_right()))
+            } else {
+                (ray.dir, None)
+            }
+        }
+    };
+    let ray = *ray;
+    [Some(first), second]
+        .into_iter()
+        .flatten()
+        .filter_map(move |dir| ray.step(map, dir))
 }
 
-fn get_energized_tile_count(map: &Vec<Vec<Item>>, start_ray: Ray) -> usize {
-    let num_rows = map.len();
-    let num_cols = if let Some(row) = map.first() {
-        row.len()
-    } else {
-        0
-    };
+/// Positions of the tiles that are energized by a beam starting with `start_ray`.
+fn trace(map: &Grid<Item>, start_ray: Ray) -> Vec<(usize, usize)> {
+    // Directions of the rays seen on every tile, one bit per direction. Every
+    // ray is visited once, which also stops beams that run in loops.
+    let mut ray_map: Grid<u8> = Grid::new(map.width(), map.height(), 0);
+    search::traverse(
+        [start_ray],
+        |ray| next_rays(map, ray),
+        |ray| {
+            let bit = 1 << ray.dir as u8;
+            let seen = &mut ray_map[ray.pos];
+            let new = *seen & bit == 0;
+            *seen |= bit;
+            new
+        },
+    );
+    ray_map
+        .iter()
+        .filter(|(_, seen)| **seen != 0)
+        .map(|(pos, _)| pos)
+        .collect()
+}
 
-    // Ray tracing
-    let mut ray_map: Vec<Vec<BTreeSet<Direction>>> =
-        vec![vec![BTreeSet::new(); num_cols]; num_rows];
-    let mut rays: LinkedList<Ray> = LinkedList::new();
-    rays.push_back(start_ray);
-    'next_ray: while let Some(mut ray) = rays.pop_front() {
-        loop {
-            // Check / update ray map
-            let ray_set = &mut ray_map[ray.row][ray.col];
-            if ray_set.contains(&ray.dir) {
-                continue 'next_ray;
-            }
-            ray_set.insert(ray.dir);
-
-            // Update direction or split ray in two
-            match map[ray.row][ray.col] {
-                Item::Empty => (),
-                Item::MirrorUpRight => match ray.dir {
-                    Direction::Left => ray.dir = Direction::Down,
-                    Direction::Right => ray.dir = Direction::Up,
-                    Direction::Up => ray.dir = Direction::Right,
-                    Direction::Down => ray.dir = Direction::Left,
-                },
-                Item::MirrorUpLeft => match ray.dir {
-                    Direction::Left => ray.dir = Direction::Up,
-                    Direction::Right => ray.dir = Direction::Down,
-                    Direction::Up => ray.dir = Direction::Left,
-                    Direction::Down => ray.dir = Direction::Right,
-                },
-                Item::SplitterVertical => match ray.dir {
-                    Direction::Left | Direction::Right => {
-                        if ray.row > 0 {
-                            rays.push_back(Ray {
-                                row: ray.row - 1,
-                                col: ray.col,
-                                dir: Direction::Up,
-                            });
-                        }
-                        if ray.row < num_rows - 1 {
-                            rays.push_back(Ray {
-                                row: ray.row + 1,
-                                col: ray.col,
-                                dir: Direction::Down,
-                            });
-                        }
-                        continue 'next_ray;
-                    }
-                    _ => (),
-                },
-                Item::SplitterHorizontal => match ray.dir {
-                    Direction::Up | Direction::Down => {
-                        if ray.col > 0 {
-                            rays.push_back(Ray {
-                                row: ray.row,
-                                col: ray.col - 1,
-                                dir: Direction::Left,
-                            });
-                        }
-                        if ray.col < num_cols - 1 {
-                            rays.push_back(Ray {
-                                row: ray.row,
-                                col: ray.col + 1,
-                                dir: Direction::Right,
-                            });
-                        }
-                        continue 'next_ray;
-                    }
-                    _ => (),
-                },
-            }
+/// Number of tiles that are energized by a beam starting with `start_ray`.
+pub fn get_energized_tile_count(map: &Grid<Item>, start_ray: Ray) -> usize {
+    trace(map, start_ray).len()
+}
 
-            // Move ray one step in the current direction
-            match ray.dir {
-                Direction::Left => {
-                    if ray.col > 0 {
-                        ray.col -= 1;
-                    } else {
-                        continue 'next_ray;
-                    }
-                }
-                Direction::Right => {
-                    if ray.col < num_cols - 1 {
-                        ray.col += 1;
-                    } else {
-                        continue 'next_ray;
-                    }
-                }
-                Direction::Up => {
-                    if ray.row > 0 {
-                        ray.row -= 1;
-                    } else {
-                        continue 'next_ray;
-                    }
-                }
-                Direction::Down => {
-                    if ray.row < num_rows - 1 {
-                        ray.row += 1;
-                    } else {
-                        continue 'next_ray;
-                    }
-                }
-            }
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Grid<Item>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        // Contruct map
+        let map = Grid::parse(DAY, contents, |c| match c {
+            '.' => Some(Item::Empty),
+            '/' => Some(Item::MirrorUpRight),
+            '\\' => Some(Item::MirrorUpLeft),
+            '-' => Some(Item::SplitterHorizontal),
+            '|' => Some(Item::SplitterVertical),
+            _ => None,
+        })?;
+        if map.is_empty() {
+            return Err(ParseError::new(DAY, 1, 1, "", "empty map"));
         }
+        Ok(map)
     }
 
-    // Compute number of energized tiles
-    let energized_tile_count = ray_map
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|set| if set.is_empty() { 0 } else { 1 })
-                .sum::<usize>()
-        })
-        .sum::<usize>();
-
-    energized_tile_count
-}
+    fn part1(input: &Self::Input) -> Answer {
+        let map = input;
+        let start_ray = Ray {
+            pos: Pos::new(0, 0),
+            dir: Direction::Right,
+        };
+        let energized = trace(map, start_ray);
+
+        // Print map with the energized tiles highlighted
+        if verbosity() >= 2 {
+            Renderer::new(map, |item| match item {
+                Item::Empty => ('.', Color::Default),
+                Item::MirrorUpRight => ('/', Color::Cyan),
+                Item::MirrorUpLeft => ('\\', Color::Cyan),
+                Item::SplitterVertical => ('|', Color::Magenta),
+                Item::SplitterHorizontal => ('-', Color::Magenta),
+            })
+            .highlight(energized.iter().copied(), Color::Yellow)
+            .print();
+        }
 
-pub fn problem1() {
-    println!("problem 1");
+        image::save_if_enabled("day16_energized", || {
+            Exporter::new(map, |item| match item {
+                Item::Empty => image::BLACK,
+                Item::MirrorUpRight | Item::MirrorUpLeft => image::BLUE,
+                Item::SplitterVertical | Item::SplitterHorizontal => image::RED,
+            })
+            .overlay(energized.iter().copied(), image::YELLOW)
+            .to_image()
+        });
+
+        energized.len().into()
+    }
 
-    let map = read_map();
-    let start_ray = Ray {
-        row: 0,
-        col: 0,
-        dir: Direction::Right,
-    };
-    let energized_tile_count = get_energized_tile_count(&map, start_ray);
+    fn part2(input: &Self::Input) -> Answer {
+        let map = input;
+
+        let num_rows = map.height();
+        let num_cols = map.width();
+
+        let mut max_energized_tile_count = 0;
+        for row in 0..num_rows {
+            let start_ray = Ray {
+                pos: Pos::new(row, 0),
+                dir: Direction::Right,
+            };
+            max_energized_tile_count = std::cmp::max(
+                max_energized_tile_count,
+                get_energized_tile_count(map, start_ray),
+            );
+            let start_ray = Ray {
+                pos: Pos::new(row, num_cols - 1),
+                dir: Direction::Left,
+            };
+            max_energized_tile_count = std::cmp::max(
+                max_energized_tile_count,
+                get_energized_tile_count(map, start_ray),
+            );
+        }
+        for col in 0..num_cols {
+            let start_ray = Ray {
+                pos: Pos::new(0, col),
+                dir: Direction::Down,
+            };
+            max_energized_tile_count = std::cmp::max(
+                max_energized_tile_count,
+                get_energized_tile_count(map, start_ray),
+            );
+            let start_ray = Ray {
+                pos: Pos::new(num_rows - 1, col),
+                dir: Direction::Up,
+            };
+            max_energized_tile_count = std::cmp::max(
+                max_energized_tile_count,
+                get_energized_tile_count(map, start_ray),
+            );
+        }
 
-    println!("Answer: {}", energized_tile_count);
+        max_energized_tile_count.into()
+    }
 }
 
-pub fn problem2() {
-    println!("problem 2");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
 
-    let map = read_map();
+    const EXAMPLE: &str = include_str!("../../resources/day16_example");
 
-    let num_rows = map.len();
-    let num_cols = if let Some(row) = map.first() {
-        row.len()
-    } else {
-        0
-    };
-
-    let mut max_energized_tile_count = 0;
-    for row in 0..num_rows {
-        let start_ray = Ray {
-            row,
-            col: 0,
-            dir: Direction::Right,
-        };
-        max_energized_tile_count = std::cmp::max(
-            max_energized_tile_count,
-            get_energized_tile_count(&map, start_ray),
-        );
-        let start_ray = Ray {
-            row,
-            col: num_cols - 1,
-            dir: Direction::Left,
-        };
-        max_energized_tile_count = std::cmp::max(
-            max_energized_tile_count,
-            get_energized_tile_count(&map, start_ray),
-        );
-    }
-    for col in 0..num_cols {
-        let start_ray = Ray {
-            row: 0,
-            col,
-            dir: Direction::Down,
-        };
-        max_energized_tile_count = std::cmp::max(
-            max_energized_tile_count,
-            get_energized_tile_count(&map, start_ray),
-        );
-        let start_ray = Ray {
-            row: num_rows - 1,
-            col,
-            dir: Direction::Up,
-        };
-        max_energized_tile_count = std::cmp::max(
-            max_energized_tile_count,
-            get_energized_tile_count(&map, start_ray),
-        );
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day16.run(EXAMPLE, Part::One), Ok(Answer::from(46)));
     }
 
-    println!("Answer: {}", max_energized_tile_count);
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day16.run(EXAMPLE, Part::Two), Ok(Answer::from(51)));
+    }
 }
@@ -1,68 +1,92 @@
-use std::{cmp, fs, path::Path};
+use std::cmp;
 
-pub fn problem1() {
-    println!("problem 1");
+use crate::{
+    answer::Answer,
+    parsing::{self, ParseError},
+    solution::Solution,
+};
 
-    // Determine which games would have been possible if the bag had been
-    // loaded with only 12 red cubes, 13 green cubes, and 14 blue cubes
+const DAY: u32 = 2;
 
-    let path = Path::new("resources/day2_input");
-    let contents = fs::read_to_string(path).unwrap();
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CubeSet {
+    pub red: i32,
+    pub green: i32,
+    pub blue: i32,
+}
 
-    let mut sum = 0;
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub id: i32,
+    pub sets: Vec<CubeSet>,
+}
 
-    contents.lines().into_iter().for_each(|line| {
-        let (game, sets) = line.split_once(':').unwrap();
-        let game_num = game.split_once(' ').unwrap().1.parse::<i32>().unwrap();
+pub struct Day2;
 
-        let ok = sets.split(';').into_iter().all(|set| {
-            set.split(",").into_iter().all(|s| {
-                let (num_str, color) = s.trim().split_once(' ').unwrap();
-                let num = num_str.parse::<i32>().unwrap();
-                match color {
-                    "red" => num <= 12,
-                    "green" => num <= 13,
-                    "blue" => num <= 14,
-                    _ => false,
-                }
-            })
-        });
+impl Solution for Day2 {
+    type Input = Vec<Game>;
 
-        if ok {
-            sum += game_num;
-        }
-    });
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parsing::lines(DAY, contents)
+            .map(|line| {
+                let (id, sets): (i32, &str) = line.scan(line.text(), "Game {}: {}")?;
 
-    println!("Answer: {}", sum);
-}
+                let sets = sets
+                    .split(';')
+                    .map(|set| {
+                        let mut cubes = CubeSet::default();
+                        for s in set.split(',') {
+                            let (num, color): (i32, &str) = line.scan(s.trim(), "{} {}")?;
+                            match color {
+                                "red" => cubes.red = num,
+                                "green" => cubes.green = num,
+                                "blue" => cubes.blue = num,
+                                _ => return Err(line.error(color, "unknown color")),
+                            }
+                        }
+                        Ok(cubes)
+                    })
+                    .collect::<Result<Vec<CubeSet>, ParseError>>()?;
 
-pub fn problem2() {
-    println!("problem 2");
+                Ok(Game { id, sets })
+            })
+            .collect()
+    }
 
-    let path = Path::new("resources/day2_input");
-    let contents = fs::read_to_string(path).unwrap();
+    fn part1(input: &Self::Input) -> Answer {
+        // Determine which games would have been possible if the bag had been
+        // loaded with only 12 red cubes, 13 green cubes, and 14 blue cubes
 
-    let mut sum = 0;
+        let mut sum = 0;
 
-    contents.lines().into_iter().for_each(|line| {
-        let (_, sets) = line.split_once(':').unwrap();
+        input.iter().for_each(|game| {
+            let ok = game
+                .sets
+                .iter()
+                .all(|set| set.red <= 12 && set.green <= 13 && set.blue <= 14);
 
-        let mut max_count = (0, 0, 0);
-        sets.split(';').into_iter().for_each(|set| {
-            set.split(",").into_iter().for_each(|s| {
-                let (num_str, color) = s.trim().split_once(' ').unwrap();
-                let num = num_str.parse::<i32>().unwrap();
-                match color {
-                    "red" => max_count.0 = cmp::max(max_count.0, num),
-                    "green" => max_count.1 = cmp::max(max_count.1, num),
-                    "blue" => max_count.2 = cmp::max(max_count.2, num),
-                    _ => {}
-                }
-            })
+            if ok {
+                sum += game.id;
+            }
         });
 
-        sum += max_count.0 * max_count.1 * max_count.2;
-    });
+        sum.into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let mut sum = 0;
+
+        input.iter().for_each(|game| {
+            let mut max_count = (0, 0, 0);
+            game.sets.iter().for_each(|set| {
+                max_count.0 = cmp::max(max_count.0, set.red);
+                max_count.1 = cmp::max(max_count.1, set.green);
+                max_count.2 = cmp::max(max_count.2, set.blue);
+            });
+
+            sum += max_count.0 * max_count.1 * max_count.2;
+        });
 
-    println!("Answer: {}", sum);
+        sum.into()
+    }
 }
@@ -1,73 +1,90 @@
-use std::{fs, path::Path};
-
-pub fn problem1() {
-    println!("problem 1");
-
-    let path = Path::new("resources/day4_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut sum = 0;
+use crate::{
+    answer::Answer,
+    parsing::{self, ParseError},
+    solution::Solution,
+};
+
+const DAY: u32 = 4;
+
+#[derive(Debug, Clone)]
+pub struct Card {
+    pub winning: Vec<u32>,
+    pub numbers: Vec<u32>,
+}
 
-    contents.lines().into_iter().for_each(|line| {
-        let (_, numbers) = line.split_once(':').unwrap();
-        let (winning_str, our_str) = numbers.split_once('|').unwrap();
+impl Card {
+    fn matches(&self) -> usize {
+        self.numbers
+            .iter()
+            .filter(|n| self.winning.contains(n))
+            .count()
+    }
+}
 
-        let winning_vec = winning_str
-            .trim()
-            .split_whitespace()
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect::<Vec<u32>>();
+pub struct Day4;
 
-        let count = our_str
-            .split_whitespace()
-            .filter(|n| winning_vec.contains(&n.parse::<u32>().unwrap()))
-            .count();
+impl Solution for Day4 {
+    type Input = Vec<Card>;
 
-        if count > 0 {
-            sum += (2 as u32).pow((count - 1) as u32);
-        }
-    });
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parsing::lines(DAY, contents)
+            .map(|line| {
+                let (_, winning_str, our_str): (u32, &str, &str) =
+                    line.scan(line.text(), "Card {}: {} | {}")?;
 
-    println!("Answer: {}", sum);
-}
+                let winning = line.parse_all::<u32>(winning_str)?;
+                let numbers = line.parse_all::<u32>(our_str)?;
 
-pub fn problem2() {
-    println!("problem 2");
+                Ok(Card { winning, numbers })
+            })
+            .collect()
+    }
 
-    let path = Path::new("resources/day4_input");
-    let contents = fs::read_to_string(path).unwrap();
+    fn part1(input: &Self::Input) -> Answer {
+        let mut sum = 0;
 
-    let num_cards = contents.lines().into_iter().count();
-    let mut copies: Vec<usize> = vec![1; num_cards];
+        input.iter().for_each(|card| {
+            let count = card.matches();
+            if count > 0 {
+                sum += 2_u32.pow((count - 1) as u32);
+            }
+        });
 
-    contents
-        .lines()
-        .into_iter()
-        .enumerate()
-        .for_each(|(line_inx, line)| {
-            let (_, numbers) = line.split_once(':').unwrap();
-            let (winning_str, our_str) = numbers.split_once('|').unwrap();
+        sum.into()
+    }
 
-            let winning_vec = winning_str
-                .trim()
-                .split_whitespace()
-                .map(|n| n.parse::<u32>().unwrap())
-                .collect::<Vec<u32>>();
+    fn part2(input: &Self::Input) -> Answer {
+        let num_cards = input.len();
+        let mut copies: Vec<usize> = vec![1; num_cards];
 
-            let count = our_str
-                .split_whitespace()
-                .filter(|n| winning_vec.contains(&n.parse::<u32>().unwrap()))
-                .count();
+        input.iter().enumerate().for_each(|(line_inx, card)| {
+            let count = card.matches();
 
             let copies_of_current = copies[line_inx];
-            for inx in line_inx + 1..line_inx + 1 + count {
-                if inx > num_cards - 1 {
-                    break;
-                }
-                copies[inx] += copies_of_current;
+            for copies_of_next in copies.iter_mut().skip(line_inx + 1).take(count) {
+                *copies_of_next += copies_of_current;
             }
         });
 
-    let sum: usize = copies.into_iter().sum();
-    println!("Answer: {}", sum);
+        let sum: usize = copies.into_iter().sum();
+        sum.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
+
+    const EXAMPLE: &str = include_str!("../../resources/day4_example");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day4.run(EXAMPLE, Part::One), Ok(Answer::from(13)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day4.run(EXAMPLE, Part::Two), Ok(Answer::from(30)));
+    }
 }
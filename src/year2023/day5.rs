@@ -1,8 +1,17 @@
 use itertools::Itertools;
-use std::{collections::HashMap, fs, path::Path};
+use std::collections::HashMap;
+
+use crate::{
+    answer::Answer,
+    interval::{Interval, IntervalSet, RangeMap},
+    parsing::{self, ParseError},
+    solution::Solution,
+};
+
+const DAY: u32 = 5;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum MapType {
+pub enum MapType {
     SeedToSoil,
     SoilToFertilizer,
     FertilizerToWater,
@@ -12,6 +21,16 @@ enum MapType {
     HumidityToLocation,
 }
 
+pub const ALL_MAP_TYPES: [MapType; 7] = [
+    MapType::SeedToSoil,
+    MapType::SoilToFertilizer,
+    MapType::FertilizerToWater,
+    MapType::WaterToLight,
+    MapType::LightToTemperature,
+    MapType::TemperatureToHumidity,
+    MapType::HumidityToLocation,
+];
+
 fn name_to_map_type(name: &str) -> Option<MapType> {
     match name {
         "seed-to-soil" => Some(MapType::SeedToSoil),
@@ -25,192 +44,145 @@ fn name_to_map_type(name: &str) -> Option<MapType> {
     }
 }
 
-fn map(source: u64, map: &Vec<(u64, u64, u64)>) -> u64 {
-    // Each map entry has the following values:
-    // (destination_range_start, source_range_start, range_length)
-    for (dest_start, source_start, length) in map {
-        if *source_start <= source && source <= source_start + length - 1 {
-            return dest_start + (source - source_start);
-        }
-    }
-    source
+#[derive(Debug, Clone)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    /// The seed numbers read as pairs of (range start, range length).
+    pub seed_ranges: IntervalSet<u64>,
+    pub maps: HashMap<MapType, RangeMap>,
 }
 
-fn map2(sources: &Vec<(u64, u64)>, map: &Vec<(u64, u64, u64)>) -> Vec<(u64, u64)> {
-    // Each map entry has the following values:
-    // (destination_range_start, source_range_start, range_length)
-
-    let mut destinations: Vec<(u64, u64)> = Vec::new();
-    let mut sources_left = sources.clone();
-
-    while let Some((source_first, source_length)) = sources_left.pop() {
-        let source_last = source_first + source_length - 1;
-        let mut found = false;
-        for (map_dest_first, map_source_first, map_length) in map {
-            let map_dest_last = *map_dest_first + map_length - 1;
-            let map_source_last = map_source_first + map_length - 1;
-            if *map_source_first <= source_first && source_last <= map_source_last {
-                // Whole source range contained in this mapping. Can map to single destination range.
-                destinations.push((
-                    map_dest_first + (source_first - *map_source_first),
-                    source_length,
-                ));
-                found = true;
-                break;
-            } else if *map_source_first <= source_first
-                && source_first <= map_source_last
-                && map_source_last < source_last
-            {
-                // First part of source range contained in this mapping, but not whole range.
-                let dest_len = map_source_last - source_first + 1;
-                destinations.push((map_dest_last - dest_len + 1, dest_len));
-                sources_left.push((
-                    map_source_last + 1,
-                    source_length - (map_source_last - source_first + 1),
-                ));
-                found = true;
-                break;
-            } else if source_first < *map_source_first
-                && *map_source_first <= source_last
-                && source_last <= map_source_last
-            {
-                // Last part of source range contained in this mapping, but not whole range.
-                destinations.push((*map_dest_first, source_last - *map_source_first + 1));
-                sources_left.push((
-                    source_first,
-                    source_length - (source_last - *map_source_first + 1),
-                ));
-                found = true;
-                break;
-            }
-        }
-        if !found {
-            // Whole source range is outside of any mapping range. Maps to itself.
-            destinations.push((source_first, source_length));
-        }
+impl Almanac {
+    /// All maps combined into a single map from seed to location.
+    pub fn seed_to_location(&self) -> RangeMap {
+        ALL_MAP_TYPES.iter().fold(RangeMap::new(), |map, map_type| {
+            map.compose(&self.maps[map_type])
+        })
     }
-
-    destinations
 }
 
-pub fn problem1() {
-    println!("problem 1");
+pub struct Day5;
 
-    let path = Path::new("resources/day5_input");
-    let contents = fs::read_to_string(path).unwrap();
+impl Solution for Day5 {
+    type Input = Almanac;
 
-    let mut seeds: Vec<u64> = Vec::new();
-    let mut maps: HashMap<MapType, Vec<(u64, u64, u64)>> = HashMap::new();
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let mut maps: HashMap<MapType, RangeMap> = HashMap::new();
 
-    // Parse input file
-    let mut current_map: Option<MapType> = None;
-    contents.lines().into_iter().for_each(|line| {
+        // The seeds are followed by one paragraph per map
+        let paragraphs = parsing::paragraphs(DAY, contents);
+        let Some((seeds_lines, map_paragraphs)) = paragraphs.split_first() else {
+            return Err(ParseError::new(DAY, 1, 1, "", "missing seeds"));
+        };
+        let line = seeds_lines[0];
+        let (_, seeds) = line.key_values::<u64>(line.text())?;
         if seeds.is_empty() {
-            let (_, numbers) = line.split_once(':').unwrap();
-            seeds = numbers
-                .trim()
-                .split(' ')
-                .into_iter()
-                .map(|n| n.parse::<u64>().unwrap())
-                .collect();
-        } else if line.is_empty() {
-            current_map = None;
-        } else if current_map.is_none() {
-            let (map_name, _) = line.split_once(' ').unwrap();
-            current_map = Some(name_to_map_type(map_name).unwrap());
-        } else {
-            maps.entry(*current_map.as_ref().unwrap())
-                .or_insert(Vec::new())
-                .push(
-                    line.trim()
-                        .split(' ')
-                        .map(|n| n.parse::<u64>().unwrap())
-                        .collect_tuple()
-                        .unwrap(),
-                );
+            return Err(line.error(line.text(), "expected at least one seed"));
+        }
+        if seeds.len() % 2 != 0 {
+            return Err(line.error(line.text(), "expected pairs of seed numbers"));
+        }
+        let mut seed_ranges = IntervalSet::new();
+        for (start, length) in seeds.iter().copied().tuples() {
+            match Interval::checked_with_length(start, length) {
+                Some(range) if !range.is_empty() => seed_ranges.insert(range),
+                Some(_) => return Err(line.error(line.text(), "empty seed range")),
+                None => return Err(line.error(line.text(), "range does not fit in u64")),
+            }
         }
-    });
-
-    let mut min_location = None;
-    for seed in seeds {
-        let soil = map(seed, maps.get(&MapType::SeedToSoil).unwrap());
-        let fert = map(soil, maps.get(&MapType::SoilToFertilizer).unwrap());
-        let watr = map(fert, maps.get(&MapType::FertilizerToWater).unwrap());
-        let ligh = map(watr, maps.get(&MapType::WaterToLight).unwrap());
-        let temp = map(ligh, maps.get(&MapType::LightToTemperature).unwrap());
-        let humi = map(temp, maps.get(&MapType::TemperatureToHumidity).unwrap());
-        let loca = map(humi, maps.get(&MapType::HumidityToLocation).unwrap());
-
-        if min_location.is_none() || loca < min_location.unwrap() {
-            min_location = Some(loca);
+
+        for lines in map_paragraphs {
+            let line = lines[0];
+            let (map_name,): (&str,) = line.scan(line.text(), "{} map:")?;
+            let map_type =
+                name_to_map_type(map_name).ok_or_else(|| line.error(map_name, "unknown map"))?;
+            let map = maps.entry(map_type).or_default();
+
+            for line in &lines[1..] {
+                let text = line.text();
+                // (destination_range_start, source_range_start, range_length)
+                let (dest_start, source_start, length) = line.scan(text, "{} {} {}")?;
+                let (Some(source), Some(_)) = (
+                    Interval::checked_with_length(source_start, length),
+                    Interval::checked_with_length(dest_start, length),
+                ) else {
+                    return Err(line.error(text, "range does not fit in u64"));
+                };
+                if !map.insert(source, dest_start) {
+                    return Err(line.error(text, "overlaps an earlier range"));
+                }
+            }
+        }
+
+        if let Some(map_type) = ALL_MAP_TYPES.iter().find(|t| !maps.contains_key(t)) {
+            return Err(ParseError::new(
+                DAY,
+                contents.lines().count().max(1),
+                1,
+                "",
+                format!("missing {:?} map", map_type),
+            ));
         }
+
+        Ok(Almanac {
+            seeds,
+            seed_ranges,
+            maps,
+        })
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let seed_to_location = input.seed_to_location();
+
+        let min_location = input
+            .seeds
+            .iter()
+            .map(|seed| seed_to_location.get(*seed))
+            .min();
+
+        min_location.unwrap().into()
     }
 
-    println!("Answer: {}", min_location.unwrap());
+    fn part2(input: &Self::Input) -> Answer {
+        let locations = input.seed_to_location().map_set(&input.seed_ranges);
+
+        locations.min().unwrap().into()
+    }
 }
 
-pub fn problem2() {
-    println!("problem 2");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
 
-    let path = Path::new("resources/day5_input");
-    let contents = fs::read_to_string(path).unwrap();
+    const EXAMPLE: &str = include_str!("../../resources/day5_example");
 
-    let mut seeds: Vec<(u64, u64)> = Vec::new();
-    let mut maps: HashMap<MapType, Vec<(u64, u64, u64)>> = HashMap::new();
+    #[test]
+    fn overflowing_range() {
+        let input = EXAMPLE.replace("50 98 2", "50 18446744073709551615 2");
+        let err = Day5::parse(&input).unwrap_err();
+        assert_eq!(err.message, "range does not fit in u64");
+    }
 
-    // Parse input file
-    let mut current_map: Option<MapType> = None;
-    contents.lines().into_iter().for_each(|line| {
-        if seeds.is_empty() {
-            let (_, numbers) = line.split_once(':').unwrap();
-            seeds = numbers
-                .trim()
-                .split(' ')
-                .into_iter()
-                .map(|n| n.parse::<u64>().unwrap())
-                .chunks(2)
-                .into_iter()
-                .filter_map(|chunk| {
-                    let mut chunk_iter = chunk.into_iter();
-                    Some((chunk_iter.next()?, chunk_iter.next()?))
-                })
-                .collect::<Vec<(u64, u64)>>();
-        } else if line.is_empty() {
-            current_map = None;
-        } else if current_map.is_none() {
-            let (map_name, _) = line.split_once(' ').unwrap();
-            current_map = Some(name_to_map_type(map_name).unwrap());
-        } else {
-            maps.entry(*current_map.as_ref().unwrap())
-                .or_insert(Vec::new())
-                .push(
-                    line.trim()
-                        .split(' ')
-                        .map(|n| n.parse::<u64>().unwrap())
-                        .collect_tuple()
-                        .unwrap(),
-                );
-        }
-    });
-
-    let mut min_location = None;
-    for seed_range in seeds {
-        let seed = vec![seed_range];
-
-        let soil = map2(&seed, maps.get(&MapType::SeedToSoil).unwrap());
-        let fert = map2(&soil, maps.get(&MapType::SoilToFertilizer).unwrap());
-        let watr = map2(&fert, maps.get(&MapType::FertilizerToWater).unwrap());
-        let ligh = map2(&watr, maps.get(&MapType::WaterToLight).unwrap());
-        let temp = map2(&ligh, maps.get(&MapType::LightToTemperature).unwrap());
-        let humi = map2(&temp, maps.get(&MapType::TemperatureToHumidity).unwrap());
-        let loca = map2(&humi, maps.get(&MapType::HumidityToLocation).unwrap());
-
-        for (location_start, _) in loca {
-            if min_location.is_none() || location_start < min_location.unwrap() {
-                min_location = Some(location_start);
-            }
-        }
+    #[test]
+    fn seed_pairs() {
+        let input = EXAMPLE.replace("seeds: 79 14 55 13", "seeds: 79 14 55");
+        let err = Day5::parse(&input).unwrap_err();
+        assert_eq!(err.message, "expected pairs of seed numbers");
+        let input = EXAMPLE.replace("seeds: 79 14 55 13", "seeds:");
+        assert!(Day5::parse(&input).is_err());
+        let input = EXAMPLE.replace("seeds: 79 14 55 13", "seeds: 18446744073709551615 5");
+        let err = Day5::parse(&input).unwrap_err();
+        assert_eq!(err.message, "range does not fit in u64");
     }
 
-    println!("Answer: {}", min_location.unwrap());
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day5.run(EXAMPLE, Part::One), Ok(Answer::from(35)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day5.run(EXAMPLE, Part::Two), Ok(Answer::from(46)));
+    }
 }
@@ -1,6 +1,15 @@
-use std::{fs, path::Path};
-
-fn compute(time: &Vec<u64>, dist: &Vec<u64>) -> u64 {
+use crate::{
+    answer::Answer,
+    math,
+    parsing::{self, ParseError},
+    solution::Solution,
+};
+
+const DAY: u32 = 6;
+
+/// Product of the number of ways to beat the record of every race, or `None`
+/// if the numbers do not fit in an `i64` or the product overflows.
+fn compute(time: &[u64], dist: &[u64]) -> Option<u64> {
     // Let
     //   t:  time race lasts
     //   d:  record distance
@@ -8,83 +17,131 @@ fn compute(time: &Vec<u64>, dist: &Vec<u64>) -> u64 {
     //   dr: distance travelled in race
     // then
     //   dr = (t - tr) * tr
-    // and so we are equal to the record if
-    //   d  = (t - tr) * tr
+    // and so we beat the record if
+    //   tr^2 - t * tr + d < 0
 
     assert_eq!(time.len(), dist.len());
-    let mut product = 1;
+    let mut product: u64 = 1;
 
     for (t, d) in time.iter().zip(dist.iter()) {
-        let s = ((*t as f64).powf(2.) - 4. * (*d as f64)).sqrt();
-        let t0 = (0.5 * (*t as f64) - 0.5 * s).ceil() as u64;
-        let t1 = (0.5 * (*t as f64) + 0.5 * s).floor() as u64;
-        let count = t1 - t0 + 1;
-        product *= count;
+        let t = i64::try_from(*t).ok()?;
+        let d = i64::try_from(*d).ok()?;
+        let count = match math::quadratic_negative(1, -t, d) {
+            Some(hold_times) => (hold_times.end() - hold_times.start() + 1) as u64,
+            None => 0,
+        };
+        product = product.checked_mul(count)?;
     }
 
-    product
+    Some(product)
 }
 
-pub fn problem1() {
-    println!("problem 1");
-
-    let path = Path::new("resources/day6_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut time: Vec<u64> = Vec::new();
-    let mut dist: Vec<u64> = Vec::new();
-
-    contents.lines().for_each(|line| {
-        let values = line
-            .split_once(':')
-            .unwrap()
-            .1
-            .trim()
-            .split(char::is_whitespace)
-            .into_iter()
-            .filter(|n| !n.is_empty())
-            .map(|n| n.parse::<u64>().expect(format!("n=|{}|", n).as_str()))
-            .collect::<Vec<u64>>();
-        if line.starts_with("Time") {
-            time = values;
-        } else if line.starts_with("Distance") {
-            dist = values;
-        }
-    });
-
-    let product = compute(&time, &dist);
+/// Concatenate the digits of all numbers into a single number, since the
+/// spaces between the numbers on the sheet of paper are bad kerning. Returns
+/// `None` if there are no numbers or the result does not fit in a `u64`.
+fn concatenate(numbers: &[u64]) -> Option<u64> {
+    numbers
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<String>()
+        .parse::<u64>()
+        .ok()
+}
 
-    println!("Answer: {}", product);
+#[derive(Debug, Clone)]
+pub struct Races {
+    pub time: Vec<u64>,
+    pub dist: Vec<u64>,
 }
 
-pub fn problem2() {
-    println!("problem 2");
-
-    let path = Path::new("resources/day6_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut time: Vec<u64> = Vec::new();
-    let mut dist: Vec<u64> = Vec::new();
-
-    contents.lines().for_each(|line| {
-        let number = line
-            .split_once(':')
-            .unwrap()
-            .1
-            .trim()
-            .split_whitespace()
-            .collect::<String>()
-            .parse::<u64>()
-            .unwrap();
-
-        if line.starts_with("Time") {
-            time.push(number);
-        } else if line.starts_with("Distance") {
-            dist.push(number);
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Races;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let mut time = None;
+        let mut dist = None;
+
+        for line in parsing::lines(DAY, contents) {
+            let (name, values) = line.key_values::<u64>(line.text())?;
+            match name {
+                "Time" => time = Some((values, line)),
+                "Distance" => dist = Some((values, line)),
+                _ => return Err(line.error(name, "expected Time or Distance")),
+            }
+        }
+
+        let missing = |name: &str| {
+            let line_count = contents.lines().count().max(1);
+            ParseError::new(DAY, line_count, 1, "", format!("missing {}", name))
+        };
+        let (time, time_line) = time.ok_or_else(|| missing("Time"))?;
+        let (dist, dist_line) = dist.ok_or_else(|| missing("Distance"))?;
+        if time.is_empty() {
+            return Err(time_line.error(time_line.text(), "expected at least one race"));
+        }
+        if dist.len() != time.len() {
+            return Err(dist_line.error(
+                dist_line.text(),
+                format!("expected {} distances", time.len()),
+            ));
+        }
+        for (values, line) in [(&time, time_line), (&dist, dist_line)] {
+            if concatenate(values).is_none() {
+                return Err(line.error(line.text(), "concatenated number does not fit in u64"));
+            }
         }
-    });
+        // The answers of both parts must be computable
+        let concatenated = |values: &[u64]| vec![concatenate(values).unwrap()];
+        if compute(&time, &dist).is_none()
+            || compute(&concatenated(&time), &concatenated(&dist)).is_none()
+        {
+            return Err(time_line.error(time_line.text(), "numbers are too large"));
+        }
+
+        Ok(Races { time, dist })
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        // Checked while parsing
+        let product = compute(&input.time, &input.dist).unwrap();
+
+        product.into()
+    }
 
-    let product = compute(&time, &dist);
+    fn part2(input: &Self::Input) -> Answer {
+        // Checked while parsing
+        let time = vec![concatenate(&input.time).unwrap()];
+        let dist = vec![concatenate(&input.dist).unwrap()];
 
-    println!("Answer: {}", product);
+        let product = compute(&time, &dist).unwrap();
+
+        product.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incomplete_sheets() {
+        assert_eq!(
+            Day6::parse("Time: 7 15").unwrap_err().message,
+            "missing Distance"
+        );
+        assert_eq!(
+            Day6::parse("Time: 7 15\nDistance: 9").unwrap_err().message,
+            "expected 2 distances"
+        );
+        assert!(Day6::parse("Time:\nDistance:").is_err());
+        assert!(Day6::parse("Time: 7 15\nDistance: 9 40").is_ok());
+        assert_eq!(
+            Day6::parse("Time: 9223372036854775808\nDistance: 9")
+                .unwrap_err()
+                .message,
+            "numbers are too large"
+        );
+    }
 }
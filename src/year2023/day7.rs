@@ -1,7 +1,13 @@
-use std::{fs, path::Path};
+use crate::{
+    answer::Answer,
+    parsing::{self, ParseError},
+    solution::Solution,
+};
+
+const DAY: u32 = 7;
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
-enum Value {
+pub enum Value {
     V2,
     V3,
     V4,
@@ -56,8 +62,9 @@ impl Value {
     }
 }
 
+/// Type of a hand, ordered from weakest to strongest.
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
-enum Type {
+pub enum Type {
     HighCard,
     OnePair,
     TwoPair,
@@ -68,8 +75,9 @@ enum Type {
 }
 
 impl Type {
-    fn from_cards(cards: &[Value; 5]) -> Type {
-        let mut cards_copy = cards.clone();
+    #[allow(clippy::nonminimal_bool)]
+    pub fn from_cards(cards: &[Value; 5]) -> Type {
+        let mut cards_copy = *cards;
         cards_copy.sort();
         let [a, b, c, d, e] = cards_copy;
         if a == b && b == c && c == d && d == e {
@@ -93,10 +101,11 @@ impl Type {
         Type::HighCard
     }
 
-    fn from_cards_with_joker(cards: &[Value; 5]) -> Type {
+    /// Strongest type of the hand when `J` cards act as jokers.
+    pub fn from_cards_with_joker(cards: &[Value; 5]) -> Type {
         // Find all Jokers
         let mut jokers: Vec<usize> = Vec::new();
-        cards.into_iter().enumerate().for_each(|(inx, card)| {
+        cards.iter().enumerate().for_each(|(inx, card)| {
             if *card == Value::J {
                 jokers.push(inx);
             }
@@ -110,9 +119,9 @@ impl Type {
         // Loop over all non-Joker characters, and replace all Jokers with that character.
         // Then compute the type from each hand, and find the maximum type.
         let mut max_type: Option<Type> = None;
-        cards.into_iter().for_each(|card| {
+        cards.iter().for_each(|card| {
             if *card != Value::J {
-                let mut cards_clone = cards.clone();
+                let mut cards_clone = *cards;
                 for inx in &jokers {
                     cards_clone[*inx] = *card;
                 }
@@ -126,115 +135,124 @@ impl Type {
     }
 }
 
+/// A hand of cards, ordered first by type and then card by card.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord)]
-struct Hand {
-    hand_type: Type,
-    cards: [Value; 5],
+pub struct Hand {
+    pub hand_type: Type,
+    pub cards: [Value; 5],
 }
 
 impl Hand {
-    fn get_cards(s: &str) -> Option<[Value; 5]> {
+    pub fn get_cards(s: &str) -> Option<[Value; 5]> {
         if s.len() != 5 {
             return None;
         }
+        let mut chars = s.chars();
         Some([
-            Value::from_char(s.chars().nth(0)?)?,
-            Value::from_char(s.chars().nth(1)?)?,
-            Value::from_char(s.chars().nth(2)?)?,
-            Value::from_char(s.chars().nth(3)?)?,
-            Value::from_char(s.chars().nth(4)?)?,
+            Value::from_char(chars.next()?)?,
+            Value::from_char(chars.next()?)?,
+            Value::from_char(chars.next()?)?,
+            Value::from_char(chars.next()?)?,
+            Value::from_char(chars.next()?)?,
         ])
     }
 
-    fn from_str(s: &str) -> Option<Hand> {
-        let cards = Hand::get_cards(s)?;
-        Some(Hand {
+    pub fn new(cards: [Value; 5]) -> Hand {
+        Hand {
             cards,
             hand_type: Type::from_cards(&cards),
-        })
+        }
     }
 
-    fn from_str_with_joker(s: &str) -> Option<Hand> {
-        let cards = Hand::get_cards(s)?;
-        Some(Hand {
+    pub fn with_joker(cards: [Value; 5]) -> Hand {
+        Hand {
             cards,
             hand_type: Type::from_cards_with_joker(&cards),
-        })
+        }
     }
 }
 
-pub fn problem1() {
-    println!("problem 1");
-
-    let path = Path::new("resources/day7_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut hands = contents
-        .lines()
-        .into_iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let (hand_str, bid) = line.split_once(' ').unwrap();
-            let hand = Hand::from_str(hand_str).unwrap();
-            (hand, bid.parse::<usize>().unwrap())
-        })
-        .collect::<Vec<(Hand, usize)>>();
-
-    // Sort in order by hand
-    hands.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
-
-    // Compute winnings
+/// Compute the total winnings of a list of hands and bids, sorted by rank.
+pub fn total_winnings(hands: &[(Hand, usize)]) -> usize {
     let mut total_winnings = 0;
-    hands.into_iter().enumerate().for_each(|(inx, hand)| {
+    hands.iter().enumerate().for_each(|(inx, hand)| {
         let rank = inx + 1;
         let bid = hand.1;
         total_winnings += rank * bid;
     });
+    total_winnings
+}
 
-    println!("Answer: {}", total_winnings);
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<([Value; 5], usize)>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parsing::lines(DAY, contents)
+            .filter(|line| !line.text().is_empty())
+            .map(|line| {
+                let (hand_str, bid): (&str, usize) = line.scan(line.text(), "{} {}")?;
+                let cards = Hand::get_cards(hand_str)
+                    .ok_or_else(|| line.error(hand_str, "expected five cards"))?;
+                Ok((cards, bid))
+            })
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let mut hands = input
+            .iter()
+            .map(|(cards, bid)| (Hand::new(*cards), *bid))
+            .collect::<Vec<(Hand, usize)>>();
+
+        // Sort in order by hand
+        hands.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+
+        total_winnings(&hands).into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let mut hands = input
+            .iter()
+            .map(|(cards, bid)| (Hand::with_joker(*cards), *bid))
+            .collect::<Vec<(Hand, usize)>>();
+
+        // Sort in order by hand (sort first cards using J as the weakest card)
+        hands.sort_by(|lhs, rhs| {
+            lhs.0
+                .cards
+                .into_iter()
+                .map(|v| v.joker_value())
+                .collect::<Vec<u8>>()
+                .cmp(
+                    &rhs.0
+                        .cards
+                        .into_iter()
+                        .map(|v| v.joker_value())
+                        .collect::<Vec<u8>>(),
+                )
+        });
+        hands.sort_by(|lhs, rhs| lhs.0.hand_type.cmp(&rhs.0.hand_type));
+
+        total_winnings(&hands).into()
+    }
 }
 
-pub fn problem2() {
-    println!("problem 2");
-
-    let path = Path::new("resources/day7_input");
-    let contents = fs::read_to_string(path).unwrap();
-
-    let mut hands = contents
-        .lines()
-        .into_iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let (hand_str, bid) = line.split_once(' ').unwrap();
-            let hand = Hand::from_str_with_joker(hand_str).unwrap();
-            (hand, bid.parse::<usize>().unwrap())
-        })
-        .collect::<Vec<(Hand, usize)>>();
-
-    // Sort in order by hand (sort first cards using J as the weakest card)
-    hands.sort_by(|lhs, rhs| {
-        lhs.0
-            .cards
-            .into_iter()
-            .map(|v| v.joker_value())
-            .collect::<Vec<u8>>()
-            .cmp(
-                &rhs.0
-                    .cards
-                    .into_iter()
-                    .map(|v| v.joker_value())
-                    .collect::<Vec<u8>>(),
-            )
-    });
-    hands.sort_by(|lhs, rhs| lhs.0.hand_type.cmp(&rhs.0.hand_type));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
 
-    // Compute winnings
-    let mut total_winnings = 0;
-    hands.into_iter().enumerate().for_each(|(inx, hand)| {
-        let rank = inx + 1;
-        let bid = hand.1;
-        total_winnings += rank * bid;
-    });
+    const EXAMPLE: &str = include_str!("../../resources/day7_example");
 
-    println!("Answer: {}", total_winnings);
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day7.run(EXAMPLE, Part::One), Ok(Answer::from(6440)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day7.run(EXAMPLE, Part::Two), Ok(Answer::from(5905)));
+    }
 }
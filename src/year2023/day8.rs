@@ -1,151 +1,334 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::collections::HashMap;
 
 use itertools::Itertools;
-use num::integer::lcm;
 
-fn rem_first_and_last(value: &str) -> &str {
-    let mut chars = value.chars();
-    chars.next();
-    chars.next_back();
-    chars.as_str()
-}
+use crate::{
+    answer::Answer,
+    cycle::{self, Cycle},
+    math,
+    parsing::{self, ParseError},
+    solution::Solution,
+    verbose,
+};
+
+const DAY: u32 = 8;
 
 #[derive(Clone, Copy, Debug)]
-enum Direction {
+pub enum Instruction {
     L,
     R,
 }
 
-pub fn problem1() {
-    println!("problem 1");
-
-    let path = Path::new("resources/day8_input");
-    let contents = fs::read_to_string(path).unwrap();
+#[derive(Debug, Clone)]
+pub struct Network {
+    pub instructions: Vec<Instruction>,
+    pub map: HashMap<String, (String, String)>,
+}
 
-    let mut instructions: Vec<Direction> = Vec::new();
-    let mut map: HashMap<&str, (&str, &str)> = HashMap::new();
+impl Network {
+    fn step<'a>(&'a self, pos: &str, instruction: Instruction) -> &'a str {
+        let (left, right) = self.map.get(pos).unwrap();
+        match instruction {
+            Instruction::L => left,
+            Instruction::R => right,
+        }
+    }
 
-    // Parse input
-    contents
-        .lines()
-        .into_iter()
-        .filter(|line| !line.is_empty())
-        .for_each(|line| {
-            if instructions.is_empty() {
-                instructions = line
-                    .chars()
-                    .into_iter()
-                    .map(|c| match c {
-                        'L' => Some(Direction::L),
-                        'R' => Some(Direction::R),
-                        _ => None,
-                    })
-                    .flatten()
-                    .collect();
-            } else {
-                // AAA = (BBB, CCC)
-                let (from, left_right) = line.split_once('=').unwrap();
-                let (left, right) = rem_first_and_last(left_right.trim())
-                    .split_once(',')
-                    .unwrap();
-                map.insert(from.trim(), (left.trim(), right.trim()));
+    /// Whether following the instructions from node `from` ever leads to node `to`.
+    fn leads_to(&self, from: &str, to: &str) -> bool {
+        // After this many steps, a (node, instruction index) state repeats
+        let max_steps = self.map.len() * self.instructions.len();
+        let mut pos = from;
+        for instruction in self.instructions.iter().cycle().take(max_steps) {
+            if pos == to {
+                return true;
             }
+            pos = self.step(pos, *instruction);
+        }
+        pos == to
+    }
+}
+
+/// The steps at which a ghost is at a node ending with `Z`.
+#[derive(Debug, Clone)]
+struct ZHits {
+    /// Cycle in the (node, instruction index) states of the ghost.
+    cycle: Cycle,
+    /// All steps with a Z node before the cycle repeats for the first time.
+    hits: Vec<usize>,
+}
+
+impl ZHits {
+    fn new(input: &Network, start: &str) -> Self {
+        let count = input.instructions.len();
+        let (cycle, states) = cycle::find((start, 0), |(node, inx)| {
+            (
+                input.step(node, input.instructions[*inx]),
+                (inx + 1) % count,
+            )
         });
+        let hits = states
+            .iter()
+            .positions(|(node, _)| node.ends_with('Z'))
+            .collect();
+        ZHits { cycle, hits }
+    }
 
-    // Navigate map
-    let mut steps = 0;
-    let mut pos = "AAA";
-    while pos != "ZZZ" {
-        for dir in &instructions {
-            if pos == "ZZZ" {
-                break;
-            }
-            match dir {
-                Direction::L => pos = map.get(pos).unwrap().0,
-                Direction::R => pos = map.get(pos).unwrap().1,
-            }
-            steps += 1;
-        }
+    fn contains(&self, steps: usize) -> bool {
+        self.hits.contains(&self.cycle.reduce(steps))
+    }
+
+    /// The hits that repeat every cycle.
+    fn cyclic_hits(&self) -> impl Iterator<Item = usize> + Clone + '_ {
+        self.hits
+            .iter()
+            .copied()
+            .filter(|hit| *hit >= self.cycle.start)
     }
+}
+
+/// First step at which all ghosts are at a Z node, if there is one.
+fn first_common_hit(ghosts: &[ZHits]) -> Option<u64> {
+    let max_start = ghosts.iter().map(|ghost| ghost.cycle.start).max()?;
 
-    println!("Answer: {}", steps);
+    // Before all ghosts are in their cycles
+    if let Some(steps) =
+        (0..max_start).find(|steps| ghosts.iter().all(|ghost| ghost.contains(*steps)))
+    {
+        return Some(steps as u64);
+    }
+
+    // After that, pick a hit in the cycle of every ghost and find when they
+    // coincide
+    ghosts
+        .iter()
+        .map(|ghost| {
+            ghost
+                .cyclic_hits()
+                .map(|hit| (hit as i64, ghost.cycle.length as i64))
+        })
+        .multi_cartesian_product()
+        .filter_map(|congruences| {
+            let (mut steps, modulus) = math::crt(congruences)?;
+            let max_start = max_start as i64;
+            if steps < max_start {
+                steps += (max_start - steps + modulus - 1) / modulus * modulus;
+            }
+            Some(steps as u64)
+        })
+        .min()
 }
 
-pub fn problem2() {
-    println!("problem 2");
+pub struct Day8;
 
-    let path = Path::new("resources/day8_input");
-    let contents = fs::read_to_string(path).unwrap();
+impl Solution for Day8 {
+    type Input = Network;
 
-    let mut instructions: Vec<Direction> = Vec::new();
-    let mut map: HashMap<&str, (&str, &str)> = HashMap::new();
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let mut instructions: Vec<Instruction> = Vec::new();
+        let mut map: HashMap<String, (String, String)> = HashMap::new();
+        let mut references = Vec::new();
+        let mut start_line = None;
 
-    // Parse input
-    contents
-        .lines()
-        .into_iter()
-        .filter(|line| !line.is_empty())
-        .for_each(|line| {
+        // Parse input
+        for line in parsing::lines(DAY, contents).filter(|line| !line.text().is_empty()) {
             if instructions.is_empty() {
-                instructions = line
-                    .chars()
-                    .into_iter()
-                    .map(|c| match c {
-                        'L' => Some(Direction::L),
-                        'R' => Some(Direction::R),
-                        _ => None,
-                    })
-                    .flatten()
-                    .collect();
+                instructions = line.map_chars(line.text(), |c| match c {
+                    'L' => Some(Instruction::L),
+                    'R' => Some(Instruction::R),
+                    _ => None,
+                })?;
             } else {
-                // AAA = (BBB, CCC)
-                let (from, left_right) = line.split_once('=').unwrap();
-                let (left, right) = rem_first_and_last(left_right.trim())
-                    .split_once(',')
-                    .unwrap();
-                map.insert(from.trim(), (left.trim(), right.trim()));
+                let (from, left, right): (&str, &str, &str) =
+                    line.scan(line.text(), "{} = ({}, {})")?;
+                map.insert(from.to_string(), (left.to_string(), right.to_string()));
+                if from == "AAA" {
+                    start_line = Some(line);
+                }
+                references.extend([(line, left), (line, right)]);
             }
-        });
+        }
 
-    // Find starting positions
-    let mut positions: Vec<&str> = map
-        .iter()
-        .filter(|(from, _)| from.ends_with('A'))
-        .map(|(from, _)| *from)
-        .collect();
-
-    // Assuming all paths have the same jump repeating between every time it
-    // reaches the Z positions.
-    let mut jumps: Vec<u64> = Vec::new();
-    for start_pos in positions {
-        let mut pos = start_pos;
+        if instructions.is_empty() {
+            return Err(ParseError::new(DAY, 1, 1, "", "missing instructions"));
+        }
+        // Every step must lead to a node in the map
+        if let Some((line, node)) = references
+            .into_iter()
+            .find(|(_, node)| !map.contains_key(*node))
+        {
+            return Err(line.error(node, "undefined node"));
+        }
+
+        let network = Network { instructions, map };
+        // Part 1 walks from AAA to ZZZ, but inputs for part 2 only may not
+        // have an AAA node
+        if let Some(line) = start_line {
+            if !network.leads_to("AAA", "ZZZ") {
+                return Err(line.error(line.text(), "ZZZ cannot be reached from AAA"));
+            }
+        }
+        if !network.map.keys().any(|node| node.ends_with('A')) {
+            let line_count = contents.lines().count().max(1);
+            return Err(ParseError::new(
+                DAY,
+                line_count,
+                1,
+                "",
+                "no start nodes ending with A",
+            ));
+        }
+
+        Ok(network)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        assert!(
+            input.map.contains_key("AAA"),
+            "no AAA node, this input is only for part 2"
+        );
+
+        // Navigate map
         let mut steps = 0;
-        loop {
-            for dir in &instructions {
-                match dir {
-                    Direction::L => pos = map.get(pos).unwrap().0,
-                    Direction::R => pos = map.get(pos).unwrap().1,
+        let mut pos = "AAA";
+        while pos != "ZZZ" {
+            for instruction in &input.instructions {
+                if pos == "ZZZ" {
+                    break;
                 }
+                pos = input.step(pos, *instruction);
                 steps += 1;
             }
-            if pos.ends_with('Z') {
-                jumps.push(steps);
-                break;
-            }
         }
+
+        steps.into()
     }
-    println!("jumps: {:?}", jumps);
-    // jumps: [21251, 19637, 19099, 12643, 15871, 11567]
-    // LCM: 13133452426987 <-- correct answer
-    // Used online tool to compute LCM for all jumps
 
-    // let mut steps = jumps.clone();
-    // for inx in 0..steps.len() - 2 {
-    //     let lcm = lcm(steps[inx], steps[inx + 1]);
-    //     println!("lcm: {}", lcm);
-    // }
+    fn part2(input: &Self::Input) -> Answer {
+        // Find starting positions
+        let ghosts: Vec<ZHits> = input
+            .map
+            .keys()
+            .filter(|from| from.ends_with('A'))
+            .map(|from| ZHits::new(input, from))
+            .collect();
+        for ghost in &ghosts {
+            verbose!(1, "{:?}, Z at {:?}", ghost.cycle, ghost.hits);
+        }
 
-    // println!("Answer: {}", result);
+        // Usually every ghost reaches a single Z node exactly once per cycle,
+        // at the step that equals the cycle length, so that all ghosts are at
+        // a Z node after the least common multiple of the cycle lengths. The
+        // hit must be inside the cycle, or it never repeats.
+        let simple = ghosts.iter().all(|ghost| {
+            ghost.hits == [ghost.cycle.length] && ghost.cycle.start <= ghost.cycle.length
+        });
+        if simple {
+            let lengths = ghosts.iter().map(|ghost| ghost.cycle.length as u64);
+            return math::checked_lcm_all(lengths)
+                .expect("least common multiple overflows")
+                .into();
+        }
+
+        verbose!(1, "Cycles are not aligned, using the general analysis");
+        first_common_hit(&ghosts)
+            .expect("ghosts are never at Z nodes at the same time")
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
 
-    // TODO: Actually solve this problem here in code
+    const EXAMPLE1: &str = include_str!("../../resources/day8_example1");
+    const EXAMPLE2: &str = include_str!("../../resources/day8_example2");
+    const EXAMPLE3: &str = include_str!("../../resources/day8_example3");
+
+    #[test]
+    fn part1_example1() {
+        assert_eq!(Day8.run(EXAMPLE1, Part::One), Ok(Answer::from(2)));
+    }
+
+    #[test]
+    fn part1_example2() {
+        assert_eq!(Day8.run(EXAMPLE2, Part::One), Ok(Answer::from(6)));
+    }
+
+    #[test]
+    fn part2_example3() {
+        assert_eq!(Day8.run(EXAMPLE3, Part::Two), Ok(Answer::from(6)));
+    }
+
+    #[test]
+    fn part2_unaligned_cycles() {
+        // Z nodes at steps 1 and 3 of a cycle of 4, and at step 2 of a cycle of 3
+        let input = "L
+
+APA = (AQZ, AQZ)
+AQZ = (ARR, ARR)
+ARR = (ASZ, ASZ)
+ASZ = (APA, APA)
+BBA = (BQQ, BQQ)
+BQQ = (BRZ, BRZ)
+BRZ = (BBA, BBA)";
+        assert_eq!(Day8.run(input, Part::Two), Ok(Answer::from(5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "never at Z nodes")]
+    fn part2_hit_before_cycle() {
+        // The first ghost is only at a Z node at step 2, before its cycle of
+        // length 2 starts, so it is never there together with the second
+        let input = "L
+
+AAA = (XXX, XXX)
+XXX = (ZZZ, ZZZ)
+ZZZ = (PPP, PPP)
+PPP = (QQQ, QQQ)
+QQQ = (RRR, RRR)
+RRR = (QQQ, QQQ)
+BBA = (BBZ, BBZ)
+BBZ = (BBB, BBB)
+BBB = (BBB, BBB)";
+        Day8.run(input, Part::Two).unwrap();
+    }
+
+    #[test]
+    fn undefined_nodes() {
+        let err = Day8::parse("LR\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 8, "BBB"));
+        assert!(Day8::parse("").is_err());
+    }
+
+    #[test]
+    fn missing_nodes() {
+        let err =
+            Day8::parse("L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.message, "ZZZ cannot be reached from AAA");
+        assert!(Day8::parse("L\n\nBBB = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").is_err());
+    }
+
+    #[test]
+    fn common_hits() {
+        let ghost = |start, length, hits: &[usize]| ZHits {
+            cycle: Cycle { start, length },
+            hits: hits.to_vec(),
+        };
+        // A common hit before the first ghost enters its cycle
+        assert_eq!(
+            first_common_hit(&[ghost(5, 4, &[1, 6]), ghost(0, 3, &[1])]),
+            Some(1)
+        );
+        assert_eq!(
+            first_common_hit(&[ghost(0, 4, &[3]), ghost(2, 6, &[5])]),
+            Some(11)
+        );
+        assert_eq!(
+            first_common_hit(&[ghost(0, 4, &[3]), ghost(0, 6, &[4])]),
+            None
+        );
+    }
 }
@@ -1,14 +1,17 @@
-use std::{fs, path::Path};
+use crate::{
+    answer::Answer,
+    parsing::{self, ParseError},
+    solution::Solution,
+};
 
-fn solve(contents: &str, reverse: bool) -> i64 {
+const DAY: u32 = 9;
+
+/// Sum of the extrapolated next values, or previous values if `reverse`.
+pub fn solve(sequences: &[Vec<i64>], reverse: bool) -> i64 {
     let mut sum = 0;
 
-    contents.lines().for_each(|line| {
-        let mut numbers = line
-            .split_whitespace()
-            .into_iter()
-            .map(|n| n.parse::<i64>().unwrap())
-            .collect::<Vec<i64>>();
+    sequences.iter().for_each(|sequence| {
+        let mut numbers = sequence.clone();
 
         if reverse {
             numbers.reverse();
@@ -28,10 +31,10 @@ fn solve(contents: &str, reverse: bool) -> i64 {
 
             if all_zeros {
                 let mut x = numbers[outer_inx - 1];
-                for inx in outer_inx..numbers.len() {
+                for number in &numbers[outer_inx..] {
                     // x - numbers[inx] = numbers[inx - 1]
                     // => x = numbers[inx - 1] + numbers[inx]
-                    x += numbers[inx];
+                    x += number;
                 }
                 sum += x;
                 break;
@@ -42,24 +45,50 @@ fn solve(contents: &str, reverse: bool) -> i64 {
     sum
 }
 
-pub fn problem1() {
-    println!("problem 1");
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<Vec<i64>>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        parsing::lines(DAY, contents)
+            .map(|line| line.parse_all::<i64>(line.text()))
+            .collect()
+    }
 
-    let path = Path::new("resources/day9_input");
-    let contents = fs::read_to_string(path).unwrap();
+    fn part1(input: &Self::Input) -> Answer {
+        let sum = solve(input, false);
 
-    let sum = solve(&contents, false);
+        sum.into()
+    }
 
-    println!("Answer: {}", sum);
+    fn part2(input: &Self::Input) -> Answer {
+        let sum = solve(input, true);
+
+        sum.into()
+    }
 }
 
-pub fn problem2() {
-    println!("problem 2");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, Runnable};
+
+    const EXAMPLE: &str = include_str!("../../resources/day9_example");
 
-    let path = Path::new("resources/day9_input");
-    let contents = fs::read_to_string(path).unwrap();
+    #[test]
+    fn junk_tokens() {
+        let err = Day9::parse("0 3 6\n1 x3 5").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x3"));
+    }
 
-    let sum = solve(&contents, true);
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day9.run(EXAMPLE, Part::One), Ok(Answer::from(114)));
+    }
 
-    println!("Answer: {}", sum);
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day9.run(EXAMPLE, Part::Two), Ok(Answer::from(2)));
+    }
 }